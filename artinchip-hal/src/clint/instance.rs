@@ -1,6 +1,6 @@
 //! CLINT instance.
 
-use super::register::RegisterBlock;
+use super::register::{MachineTimer, RegisterBlock};
 use core::marker::PhantomData;
 
 /// CLINT instance.
@@ -22,4 +22,9 @@ impl Clint {
     pub const fn register_block(&self) -> &'static RegisterBlock {
         unsafe { &*self.reg }
     }
+
+    /// Get the machine timer registers of hart 0.
+    pub const fn machine_timer(&self) -> &'static MachineTimer {
+        unsafe { &*(self.reg as *const MachineTimer) }
+    }
 }
@@ -1,3 +1,65 @@
 //! CLINT register blocks and registers.
 
+use volatile_register::RW;
+
 pub use xuantie_riscv::peripheral::clint::THeadClint as RegisterBlock;
+
+/// CLINT machine timer registers of hart 0.
+#[repr(C)]
+pub struct MachineTimer {
+    _reserved0: [u8; 0x4000],
+    /// Machine timer compare low register (`MTIMECMPL0`).
+    #[doc(alias = "MTIMECMPL0")]
+    pub mtimecmp_low: RW<u32>,
+    /// Machine timer compare high register (`MTIMECMPH0`).
+    #[doc(alias = "MTIMECMPH0")]
+    pub mtimecmp_high: RW<u32>,
+    _reserved1: [u8; 0x7FF0],
+    /// Machine timer low register (`MTIMEL`).
+    #[doc(alias = "MTIMEL")]
+    pub mtime_low: RW<u32>,
+    /// Machine timer high register (`MTIMEH`).
+    #[doc(alias = "MTIMEH")]
+    pub mtime_high: RW<u32>,
+}
+
+impl MachineTimer {
+    /// Read the 64-bit `mtime` counter.
+    #[inline]
+    pub fn mtime(&self) -> u64 {
+        loop {
+            let hi = self.mtime_high.read();
+            let lo = self.mtime_low.read();
+            if self.mtime_high.read() == hi {
+                return ((hi as u64) << 32) | lo as u64;
+            }
+        }
+    }
+
+    /// Write the 64-bit `mtimecmp` compare value.
+    ///
+    /// The high word is parked at its maximum first so no spurious match
+    /// fires between the two writes.
+    #[inline]
+    pub fn set_mtimecmp(&self, val: u64) {
+        unsafe {
+            self.mtimecmp_high.write(u32::MAX);
+            self.mtimecmp_low.write(val as u32);
+            self.mtimecmp_high.write((val >> 32) as u32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_machine_timer_offset() {
+        assert_eq!(offset_of!(MachineTimer, mtimecmp_low), 0x4000);
+        assert_eq!(offset_of!(MachineTimer, mtimecmp_high), 0x4004);
+        assert_eq!(offset_of!(MachineTimer, mtime_low), 0xBFF8);
+        assert_eq!(offset_of!(MachineTimer, mtime_high), 0xBFFC);
+    }
+}
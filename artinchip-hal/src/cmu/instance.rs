@@ -2,6 +2,7 @@
 
 use super::register::RegisterBlock;
 use core::marker::PhantomData;
+use embedded_time::rate::Hertz;
 
 /// CMU instance.
 pub struct Cmu {
@@ -22,4 +23,12 @@ impl Cmu {
     pub const fn register_block(&self) -> &'static RegisterBlock {
         unsafe { &*self.reg }
     }
+
+    /// Get the PLL_INT1 output frequency.
+    ///
+    /// PLL_INT1 feeds the module clocks of UART, I2C and other peripherals.
+    #[inline]
+    pub fn pll_int1_freq(&self) -> Hertz {
+        Hertz(self.register_block().pll_int1_general.read().output_freq())
+    }
 }
@@ -521,6 +521,22 @@ impl PllGeneral {
     pub const fn factor_p(self) -> u8 {
         (self.0 & Self::FACTOR_P) as u8
     }
+    /// Get the pll output frequency in Hz.
+    ///
+    /// Returns the 24 MHz oscillator frequency when the output is bypassed.
+    #[inline]
+    pub const fn output_freq(self) -> u32 {
+        const OSC_FREQ: u64 = 24_000_000;
+        if matches!(self.pll_output_sel(), PllOutput::Osc24M) {
+            return OSC_FREQ as u32;
+        }
+        let m = if self.is_factor_m_enabled() {
+            self.factor_m() as u64 + 1
+        } else {
+            1
+        };
+        (OSC_FREQ * (self.factor_n() as u64 + 1) / ((self.factor_p() as u64 + 1) * m)) as u32
+    }
 }
 
 /// Ldo bias current.
@@ -2360,6 +2376,17 @@ mod tests {
         val = PllGeneral(0x0).set_factor_p(0x1);
         assert_eq!(val.factor_p(), 0x1);
         assert_eq!(val.0, 0x0000_0001);
+
+        val = PllGeneral(0x0).set_factor_n(49);
+        assert_eq!(val.output_freq(), 24_000_000);
+        val = val.set_pll_output_sel(PllOutput::PllClk);
+        assert_eq!(val.output_freq(), 1_200_000_000);
+        val = val.set_factor_m(1);
+        assert_eq!(val.output_freq(), 1_200_000_000);
+        val = val.enable_facter_m();
+        assert_eq!(val.output_freq(), 600_000_000);
+        val = val.set_factor_p(1);
+        assert_eq!(val.output_freq(), 300_000_000);
     }
 
     test_should_panic!(
@@ -1,13 +1,18 @@
 //! Direct Memory Access (DMA).
 
+pub(crate) mod cache;
 mod channel;
 mod dma_ext;
 mod instance;
+#[cfg(feature = "clic_interrupts")]
+mod non_blocking;
 mod register;
 mod task;
 
 pub use channel::*;
 pub use dma_ext::DmaExt;
 pub use instance::Dma;
+#[cfg(feature = "clic_interrupts")]
+pub use non_blocking::*;
 pub use register::*;
 pub use task::*;
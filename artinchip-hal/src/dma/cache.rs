@@ -0,0 +1,51 @@
+//! Data cache maintenance for DMA buffers.
+
+use core::sync::atomic::{Ordering, fence};
+use xuantie_riscv::asm::{dcache_cipa, dcache_ipa};
+
+#[cfg(not(feature = "d21x"))]
+const CACHE_LINE: usize = 32;
+#[cfg(feature = "d21x")]
+const CACHE_LINE: usize = 64;
+
+/// Whether `len` bytes at `addr` start and end on cache line boundaries.
+///
+/// Only such buffers may be invalidated without discarding neighbouring data.
+#[inline]
+pub(crate) const fn is_line_aligned(addr: usize, len: usize) -> bool {
+    addr.is_multiple_of(CACHE_LINE) && len.is_multiple_of(CACHE_LINE)
+}
+
+/// Write back and invalidate cached data of `len` bytes at `addr`.
+///
+/// Call before the DMA controller reads memory written by the CPU.
+#[inline]
+pub(crate) fn clean_invalidate(addr: usize, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let mut p = addr & !(CACHE_LINE - 1);
+    while p < addr + len {
+        unsafe { dcache_cipa(p) };
+        p += CACHE_LINE;
+    }
+    fence(Ordering::SeqCst);
+}
+
+/// Invalidate cached data of `len` bytes at `addr`.
+///
+/// Call before the CPU reads memory written by the DMA controller. Lines
+/// shared with other data are discarded too, so DMA receive buffers must be
+/// cache line aligned, see [`is_line_aligned`].
+#[inline]
+pub(crate) fn invalidate(addr: usize, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let mut p = addr & !(CACHE_LINE - 1);
+    while p < addr + len {
+        unsafe { dcache_ipa(p) };
+        p += CACHE_LINE;
+    }
+    fence(Ordering::SeqCst);
+}
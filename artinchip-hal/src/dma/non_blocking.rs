@@ -0,0 +1,111 @@
+//! DMA interrupt handling and async completion.
+
+use core::future::poll_fn;
+use core::sync::atomic::{AtomicU8, Ordering};
+use core::task::Poll;
+
+use embassy_sync::waitqueue::AtomicWaker;
+
+use super::channel::DmaChannel;
+use super::register::RegisterBlock;
+use crate::interrupt::clic::typelevel::{self, Interrupt as _};
+
+const DMA_BASE: usize = 0x1000_0000;
+const CHANNELS: usize = 8;
+
+static DMA_WAKERS: [AtomicWaker; CHANNELS] = [const { AtomicWaker::new() }; CHANNELS];
+static DMA_EVENTS: [AtomicU8; CHANNELS] = [const { AtomicU8::new(0) }; CHANNELS];
+
+/// DMA channel events recorded by [`DmaInterruptHandler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct DmaEvents(u8);
+
+impl DmaEvents {
+    /// Half of the current task has been transferred.
+    pub const TASK_HALF: Self = Self(1 << 0);
+    /// The current task has finished.
+    pub const TASK_FINISH: Self = Self(1 << 1);
+    /// All tasks of the chain have finished.
+    pub const ALL_FINISH: Self = Self(1 << 2);
+    /// Address or request error.
+    pub const ADDR_REQ_ERROR: Self = Self(1 << 3);
+
+    /// No events.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    /// Events in either `self` or `other`.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+    /// Check if any event of `other` is in `self`.
+    #[inline]
+    pub const fn intersects(self, other: Self) -> bool {
+        (self.0 & other.0) != 0
+    }
+    /// Check if there are no events.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// DMA interrupt handler.
+///
+/// Records per channel events, clears them and wakes the waiting tasks.
+pub struct DmaInterruptHandler;
+
+impl typelevel::Handler<typelevel::DMA> for DmaInterruptHandler {
+    unsafe fn on_interrupt() {
+        let reg = unsafe { &*(DMA_BASE as *const RegisterBlock) };
+        let status = reg.int_status.read();
+        for ch in 0..CHANNELS as u8 {
+            let mut events = DmaEvents::empty();
+            if status.is_task_half_pending(ch) {
+                events = events.union(DmaEvents::TASK_HALF);
+            }
+            if status.is_task_finish_pending(ch) {
+                events = events.union(DmaEvents::TASK_FINISH);
+            }
+            if status.is_all_finish_pending(ch) {
+                events = events.union(DmaEvents::ALL_FINISH);
+            }
+            if status.is_addr_req_err_pending(ch) {
+                events = events.union(DmaEvents::ADDR_REQ_ERROR);
+            }
+            if !events.is_empty() {
+                DMA_EVENTS[ch as usize].fetch_or(events.0, Ordering::AcqRel);
+                DMA_WAKERS[ch as usize].wake();
+            }
+        }
+        // Write back the pending bits just handled to clear them.
+        unsafe { reg.int_status.write(status) };
+        typelevel::DMA::clear_pending();
+    }
+}
+
+impl<'a, const I: u8> DmaChannel<'a, I> {
+    /// Take the events recorded by the interrupt handler since the last call.
+    #[inline]
+    pub fn take_events(&mut self) -> DmaEvents {
+        DmaEvents(DMA_EVENTS[I as usize].swap(0, Ordering::AcqRel))
+    }
+
+    /// Wait until any of `events` is recorded, returning and clearing all
+    /// recorded events.
+    pub async fn wait_for_events(&mut self, events: DmaEvents) -> DmaEvents {
+        poll_fn(|cx| {
+            DMA_WAKERS[I as usize].register(cx.waker());
+            let recorded = DmaEvents(DMA_EVENTS[I as usize].load(Ordering::Acquire));
+            if recorded.intersects(events) {
+                Poll::Ready(self.take_events())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
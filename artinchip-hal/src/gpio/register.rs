@@ -76,6 +76,11 @@ impl InputState {
         assert!(pin < 32, "Pin index out of range (expected 0..=31)");
         (self.0 & (1 << pin)) == 0
     }
+    /// Get the levels of pins selected by `mask`, one bit per pin.
+    #[inline]
+    pub const fn masked(self, mask: u32) -> u32 {
+        self.0 & mask
+    }
 }
 
 /// Output configuration register.
@@ -108,6 +113,18 @@ impl OutputConfig {
         assert!(pin < 32, "Pin index out of range (expected 0..=31)");
         Self(self.0 & !(1 << pin))
     }
+    /// Get the output levels of pins selected by `mask`, one bit per pin.
+    #[inline]
+    pub const fn masked(self, mask: u32) -> u32 {
+        self.0 & mask
+    }
+    /// Set the output levels of pins selected by `mask` to `value`.
+    ///
+    /// Bits of `value` outside `mask` are ignored.
+    #[inline]
+    pub const fn set_masked(self, mask: u32, value: u32) -> Self {
+        Self((self.0 & !mask) | (value & mask))
+    }
 }
 
 /// Interrupt enable register.
@@ -137,7 +154,7 @@ impl InterruptEnable {
 }
 
 /// Interrupt state register.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 #[repr(transparent)]
 pub struct InterruptState(u32);
 
@@ -508,6 +525,7 @@ mod tests {
             assert!(val.is_high(pin));
             assert!(!val.is_low(pin));
         }
+        assert_eq!(val.masked(0x00FF_FF00), 0x00FF_0000);
     }
 
     test_should_panic!(
@@ -535,6 +553,11 @@ mod tests {
             assert!(val.is_high(pin));
             assert!(val.is_low(pin + 16));
         }
+        assert_eq!(val.masked(0x00FF_FF00), 0x0000_FF00);
+
+        val = val.set_masked(0x00FF_FF00, 0xFF5A_A5FF);
+        assert_eq!(val.0, 0x005A_A5FF);
+        assert_eq!(val.masked(0x00FF_FF00), 0x005A_A500);
     }
 
     test_should_panic!(
@@ -36,6 +36,15 @@ impl<'a> TimerDelay<'a> {
         high | low
     }
 
+    /// Get the counter frequency in Hz.
+    pub fn tick_freq(&self) -> u32 {
+        match self.reg.cnt_status.read().fcack() {
+            CntFreq::Freq4M => 4_000_000,
+            CntFreq::Freq1M => 1_000_000,
+            CntFreq::Freq250k => 250_000,
+        }
+    }
+
     /// Free the TimerDelay and return GTC instance.
     pub fn free(self, cmu: &Cmu) -> Gtc {
         unsafe {
@@ -2,14 +2,27 @@
 
 mod blocking;
 mod config;
+mod dma;
+mod error;
 mod i2c_ext;
 mod instance;
+#[cfg(feature = "clic_interrupts")]
+mod non_blocking;
 mod pad;
 mod register;
+mod setup;
+mod smbus;
+mod target;
 
 pub use blocking::*;
 pub use config::*;
+pub use dma::i2c_dma_id;
+pub use error::Error;
 pub use i2c_ext::I2cExt;
 pub use instance::I2c;
+#[cfg(feature = "clic_interrupts")]
+pub use non_blocking::*;
 pub use pad::*;
 pub use register::*;
+pub use smbus::{SMBUS_BLOCK_MAX, Smbus, SmbusError, smbus_pec};
+pub use target::*;
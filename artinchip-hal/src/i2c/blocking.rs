@@ -1,11 +1,14 @@
 //! Blocking I2C interface.
 
 use embedded_hal::i2c::{Operation, SevenBitAddress, TenBitAddress};
+use embedded_time::rate::Hertz;
 
-use super::config::{I2cConfig, Role};
+use super::config::I2cConfig;
+use super::error::Error;
 use super::instance::I2c;
 use super::pad::I2cPads;
-use super::register::{AddressMode, RegisterBlock, SpeedMode, TransferMode};
+use super::register::{DataCommand, InterruptClear, RegisterBlock, TransferMode};
+use super::setup::{clock_register, init, set_address};
 use crate::cmu::Cmu;
 
 /// Blocking I2C interface.
@@ -13,152 +16,109 @@ pub struct BlockingI2c<'a, const I: u8, PAD>
 where
     PAD: I2cPads<I>,
 {
-    reg: &'a RegisterBlock,
+    pub(super) reg: &'a RegisterBlock,
     pad: PAD,
+    pub(super) timeout: u32,
+    freq: Hertz,
 }
 
 impl<'a, const I: u8, PAD> BlockingI2c<'a, I, PAD>
 where
     PAD: I2cPads<I>,
 {
-    // I2C fixed clock is 24Mhz.
-    const I2C_DEFAULT_CLOCK: u32 = 24_000_000;
-
     /// Create a new blocking serial.
     pub fn new(reg: &'a RegisterBlock, pad: PAD, config: I2cConfig, cmu: &mut Cmu) -> Self {
-        // Reference: https://aicdoc.artinchip.com/topics/ic/i2c/i2c-programming-guide-d13x.html
-        let clk = cmu.register_block();
-        let i2c_clk = match I {
-            0 => &clk.clock_i2c0,
-            1 => &clk.clock_i2c1,
-            2 => &clk.clock_i2c2,
-            3 => &clk.clock_i2c3,
-            _ => panic!("Invalid I2C index"),
-        };
-        unsafe {
-            // Initialize module clock.
-            // Reference: https://aicdoc.artinchip.com/topics/ic/cmu/cmu-function2-d13x.html#topic_yvp_f24_4bc__table_qb3_bn5_ydc
-            i2c_clk.modify(|v| v.enable_bus_clk());
-            i2c_clk.modify(|v| v.enable_module_reset());
-            riscv::asm::delay(500);
-            i2c_clk.modify(|v| v.disable_module_reset());
-
-            // Disable I2C module before configuration.
-            reg.enable.modify(|v| v.disable_i2c());
-
-            // Disable interrupts.
-            reg.intr_mask.modify(|v| v.disable_all());
-
-            // Configure SCL high and low counts.
-            let (scl_hcnt, scl_lcnt) = Self::calc_scl_cnt(config.speed_mode);
-            match config.speed_mode {
-                SpeedMode::Standard => {
-                    reg.ss_scl_hcnt.modify(|v| v.set_scl_high_count(scl_hcnt));
-                    reg.ss_scl_lcnt.modify(|v| v.set_scl_low_count(scl_lcnt));
-                }
-                SpeedMode::Fast => {
-                    reg.fs_scl_hcnt.modify(|v| v.set_scl_high_count(scl_hcnt));
-                    reg.fs_scl_lcnt.modify(|v| v.set_scl_low_count(scl_lcnt));
-                }
-            }
-
-            // Configure SDA hold time.
-            reg.sda_hold
-                .modify(|v| v.set_sda_tx_hold(10).set_sda_rx_hold(0));
-
-            // Configure I2C role.
-            match config.role {
-                Role::Master => reg.ctrl.modify(|v| {
-                    v.enable_master_mode()
-                        .disable_slave_mode()
-                        .set_speed_mode(config.speed_mode)
-                        .enable_restart()
-                }),
-                Role::Slave => reg.ctrl.modify(|v| {
-                    v.enable_slave_mode()
-                        .disable_master_mode()
-                        .set_speed_mode(config.speed_mode)
-                        .set_stop_detect_if_addressed(true)
-                }),
-            }
-
-            // Enable I2C.
-            reg.enable.modify(|v| v.enable_i2c());
+        let freq = init::<I>(reg, &config, cmu);
+        Self {
+            reg,
+            pad,
+            timeout: config.timeout,
+            freq,
         }
+    }
 
-        Self { reg, pad }
+    /// SCL frequency achieved for the configured frequency and bus timing.
+    #[inline]
+    pub fn freq(&self) -> Hertz {
+        self.freq
     }
 
     /// Set address.
+    #[inline]
     pub fn set_address(&mut self, address: u16) {
-        // Disable I2C.
-        unsafe {
-            self.reg.enable.modify(|v| v.disable_i2c());
+        set_address(self.reg, address)
+    }
+
+    /// Run `operations` as one transaction with `address`.
+    ///
+    /// Adjacent operations of the same direction are merged; a change of
+    /// direction issues a repeated START. On failure the bus is released and
+    /// the controller is returned to idle. Transactions that transfer no data
+    /// fail with [`Error::Protocol`].
+    fn transfer(&mut self, address: u16, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        let count = begin_transaction(self.reg, address, operations);
+        if count == 0 && !operations.is_empty() {
+            return Err(Error::Protocol);
+        }
+        let result = self.transfer_operations(&mut operations[..count]);
+        if result.is_err() {
+            recover(self.reg, self.timeout);
         }
+        result
+    }
 
-        let addr_mode = if address > 0x7F {
-            AddressMode::Bit10
-        } else {
-            AddressMode::Bit7
-        };
-        unsafe {
-            if self.reg.ctrl.read().is_master_mode_enabled() {
-                self.reg
-                    .ctrl
-                    .modify(|v| v.set_address_mode_master(addr_mode));
-                self.reg.target.modify(|v| v.set_target_address(address));
-            } else {
-                self.reg
-                    .ctrl
-                    .modify(|v| v.set_address_mode_slave(addr_mode));
-                self.reg.slave_addr.modify(|v| v.set_slave_address(address));
+    fn transfer_operations(&mut self, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        let (reg, timeout) = (self.reg, self.timeout);
+        let total_ops = operations.len();
+        if total_ops == 0 {
+            return Ok(());
+        }
+
+        for (idx, operation) in operations.iter_mut().enumerate() {
+            let is_last_op = idx == total_ops - 1;
+
+            match operation {
+                Operation::Write(bytes) => write_operation(reg, timeout, bytes, is_last_op)?,
+                Operation::Read(buffer) => read_operation(reg, timeout, buffer, is_last_op)?,
             }
         }
 
-        // Enable I2C.
-        unsafe {
-            self.reg.enable.modify(|v| v.enable_i2c());
+        wait_stop(reg, timeout)
+    }
+
+    /// Write `header` to `address`, then read a byte count `n` followed by
+    /// `n + extra` bytes into `buffer` after a repeated START.
+    ///
+    /// Returns the number of bytes read including the count, or `None` if
+    /// they do not fit in `buffer`, in which case the transfer is aborted.
+    pub(crate) fn read_counted(
+        &mut self,
+        address: u16,
+        header: &[u8],
+        extra: usize,
+        buffer: &mut [u8],
+    ) -> Result<Option<usize>, Error> {
+        assert!(!header.is_empty() && !buffer.is_empty());
+        begin_transaction(self.reg, address, &[Operation::Write(header)]);
+        let result = read_counted(self.reg, self.timeout, header, extra, buffer);
+        if !matches!(result, Ok(Some(_))) {
+            recover(self.reg, self.timeout);
         }
+        result
     }
 
-    /// Calculate SCL high and low counts.
+    /// Clear a bus left with SDA held low, e.g. by a target reset mid-transfer.
     ///
-    /// Standard mode: tHIGH_min = 4000ns, tLOW_min = 4700ns.
-    /// Fast mode: tHIGH_min = 600ns, tLOW_min = 1300ns.
+    /// Failed transfers already do this when the controller detects SDA stuck low.
     #[inline]
-    fn calc_scl_cnt(speed: SpeedMode) -> (u16, u16) {
-        // Reference: https://github.com/artinchip/luban-lite/blob/main/bsp/artinchip/hal/i2c/hal_i2c.c#L83
-        const SS_MIN_SCL_HIGH_NS: u32 = 4000; // Standard mode tHIGH min.
-        const SS_MIN_SCL_LOW_NS: u32 = 4700; // Standard mode tLOW min.
-        const FS_MIN_SCL_HIGH_NS: u32 = 600; // Fast mode tHIGH min.
-        const FS_MIN_SCL_LOW_NS: u32 = 1300; // Fast mode tLOW min.
-        const MARGIN: u16 = 2; // Safety margin.
-
-        let (high_ns, low_ns) = match speed {
-            SpeedMode::Standard => (SS_MIN_SCL_HIGH_NS, SS_MIN_SCL_LOW_NS),
-            SpeedMode::Fast => (FS_MIN_SCL_HIGH_NS, FS_MIN_SCL_LOW_NS),
-        };
-
-        // Calculate counts:  count = (time_ns * clk_freq_khz) / 1_000_000.
-        // Divide clk_freq by 1000 first to prevent overflow.
-        let clk_khz = Self::I2C_DEFAULT_CLOCK / 1000;
-        let hcnt = ((high_ns * clk_khz) / 1_000_000) as u16 + MARGIN;
-        let lcnt = ((low_ns * clk_khz) / 1_000_000) as u16 + MARGIN;
-
-        (hcnt, lcnt)
+    pub fn recover_bus(&mut self) -> Result<(), Error> {
+        recover_bus(self.reg, self.timeout)
     }
 
     /// Free the blocking I2C and return I2C instance, SCL and SDA pads.
     pub fn free(self, cmu: &Cmu) -> (I2c<I>, PAD) {
         unsafe {
-            let clk = cmu.register_block();
-            let i2c_clk = match I {
-                0 => &clk.clock_i2c0,
-                1 => &clk.clock_i2c1,
-                2 => &clk.clock_i2c2,
-                _ => &clk.clock_i2c3,
-            };
-            i2c_clk.modify(|v| v.disable_bus_clk().enable_module_reset());
+            clock_register::<I>(cmu).modify(|v| v.disable_bus_clk().enable_module_reset());
         }
         (I2c::__new(self.reg), self.pad)
     }
@@ -168,78 +128,20 @@ impl<'a, const I: u8, PAD> embedded_hal::i2c::ErrorType for BlockingI2c<'a, I, P
 where
     PAD: I2cPads<I>,
 {
-    type Error = core::convert::Infallible;
+    type Error = Error;
 }
 
 impl<'a, const I: u8, PAD> embedded_hal::i2c::I2c<SevenBitAddress> for BlockingI2c<'a, I, PAD>
 where
     PAD: I2cPads<I>,
 {
+    #[inline]
     fn transaction(
         &mut self,
         address: u8,
         operations: &mut [Operation<'_>],
     ) -> Result<(), Self::Error> {
-        self.set_address(address as u16);
-
-        let total_ops = operations.len();
-
-        for (idx, operation) in operations.iter_mut().enumerate() {
-            let is_last_op = idx == total_ops - 1;
-
-            match operation {
-                Operation::Write(bytes) => {
-                    for (byte_idx, &byte) in bytes.iter().enumerate() {
-                        while !self.reg.status.read().is_tx_fifo_not_full() {
-                            core::hint::spin_loop();
-                        }
-
-                        let is_last_byte = byte_idx == bytes.len() - 1;
-                        // Only send STOP on last byte of last operation
-                        let send_stop = is_last_op && is_last_byte;
-
-                        unsafe {
-                            // I2C_DATA_CMD[8]=0 (write), [7:0]=data, [9]=STOP
-                            self.reg.data_cmd.modify(|v| {
-                                v.set_data_byte(byte)
-                                    .set_transfer_mode(TransferMode::Write)
-                                    .set_stop(send_stop)
-                            });
-                        }
-                    }
-                }
-                Operation::Read(buffer) => {
-                    let len = buffer.len();
-
-                    // Issue read commands
-                    for byte_idx in 0..len {
-                        while !self.reg.status.read().is_tx_fifo_not_full() {
-                            core::hint::spin_loop();
-                        }
-
-                        let is_last_byte = byte_idx == len - 1;
-                        let send_stop = is_last_op && is_last_byte;
-
-                        unsafe {
-                            // I2C_DATA_CMD[8]=1 (read), [9]=STOP
-                            self.reg.data_cmd.modify(|v| {
-                                v.set_transfer_mode(TransferMode::Read).set_stop(send_stop)
-                            });
-                        }
-                    }
-
-                    // Read data from RX FIFO
-                    for byte in buffer.iter_mut() {
-                        while self.reg.rx_flr.read().rx_fifo_count() == 0 {
-                            core::hint::spin_loop();
-                        }
-                        *byte = self.reg.data_cmd.read().data_byte();
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        self.transfer(address as u16, operations)
     }
 }
 
@@ -247,70 +149,299 @@ impl<'a, const I: u8, PAD> embedded_hal::i2c::I2c<TenBitAddress> for BlockingI2c
 where
     PAD: I2cPads<I>,
 {
+    #[inline]
     fn transaction(
         &mut self,
         address: u16,
         operations: &mut [Operation<'_>],
     ) -> Result<(), Self::Error> {
-        self.set_address(address);
+        self.transfer(address, operations)
+    }
+}
 
-        let total_ops = operations.len();
+// Receive FIFO depth; read commands in flight never exceed it.
+const FIFO_DEPTH: usize = 8;
+
+/// Prepare a transaction with `address`.
+///
+/// Returns the number of leading operations to run, up to the last one that
+/// transfers data, as the controller can not address a target without
+/// transferring data.
+pub(crate) fn begin_transaction(
+    reg: &RegisterBlock,
+    address: u16,
+    operations: &[Operation<'_>],
+) -> usize {
+    let count = operations
+        .iter()
+        .rposition(|op| match op {
+            Operation::Write(bytes) => !bytes.is_empty(),
+            Operation::Read(buffer) => !buffer.is_empty(),
+        })
+        .map_or(0, |idx| idx + 1);
+    if count > 0 {
+        start_transaction(reg, address);
+    }
+    count
+}
 
-        for (idx, operation) in operations.iter_mut().enumerate() {
-            let is_last_op = idx == total_ops - 1;
+/// Address `address` and clear the status of a previous transaction.
+pub(crate) fn start_transaction(reg: &RegisterBlock, address: u16) {
+    set_address(reg, address);
+    unsafe {
+        reg.intr_clear.write(
+            InterruptClear::default()
+                .clear_tx_abort()
+                .clear_stop_detect(),
+        );
+    }
+}
 
-            match operation {
-                Operation::Write(bytes) => {
-                    for (byte_idx, &byte) in bytes.iter().enumerate() {
-                        while !self.reg.status.read().is_tx_fifo_not_full() {
-                            core::hint::spin_loop();
-                        }
-
-                        let is_last_byte = byte_idx == bytes.len() - 1;
-                        // Only send STOP on last byte of last operation
-                        let send_stop = is_last_op && is_last_byte;
-
-                        unsafe {
-                            // I2C_DATA_CMD[8]=0 (write), [7:0]=data, [9]=STOP
-                            self.reg.data_cmd.modify(|v| {
-                                v.set_data_byte(byte)
-                                    .set_transfer_mode(TransferMode::Write)
-                                    .set_stop(send_stop)
-                            });
-                        }
-                    }
-                }
-                Operation::Read(buffer) => {
-                    let len = buffer.len();
-
-                    // Issue read commands
-                    for byte_idx in 0..len {
-                        while !self.reg.status.read().is_tx_fifo_not_full() {
-                            core::hint::spin_loop();
-                        }
-
-                        let is_last_byte = byte_idx == len - 1;
-                        let send_stop = is_last_op && is_last_byte;
-
-                        unsafe {
-                            // I2C_DATA_CMD[8]=1 (read), [9]=STOP
-                            self.reg.data_cmd.modify(|v| {
-                                v.set_transfer_mode(TransferMode::Read).set_stop(send_stop)
-                            });
-                        }
-                    }
-
-                    // Read data from RX FIFO
-                    for byte in buffer.iter_mut() {
-                        while self.reg.rx_flr.read().rx_fifo_count() == 0 {
-                            core::hint::spin_loop();
-                        }
-                        *byte = self.reg.data_cmd.read().data_byte();
-                    }
-                }
+/// Write `bytes`, followed by a STOP if `stop`.
+fn write_operation(
+    reg: &RegisterBlock,
+    timeout: u32,
+    bytes: &[u8],
+    stop: bool,
+) -> Result<(), Error> {
+    for (idx, &byte) in bytes.iter().enumerate() {
+        wait_for(reg, timeout, |r| r.status.read().is_tx_fifo_not_full())?;
+        // Only send STOP on the last byte
+        push_write(reg, byte, stop && idx == bytes.len() - 1);
+    }
+    Ok(())
+}
+
+/// Read into `buffer`, followed by a STOP if `stop`.
+fn read_operation(
+    reg: &RegisterBlock,
+    timeout: u32,
+    buffer: &mut [u8],
+    stop: bool,
+) -> Result<(), Error> {
+    let mut read = ReadProgress::new(stop);
+    let mut polls = 0;
+    while !read.is_done(buffer) {
+        check_abort(reg)?;
+        if read.step(reg, buffer) {
+            polls = 0;
+        } else {
+            polls += 1;
+            if polls >= timeout {
+                return Err(Error::Timeout);
+            }
+            core::hint::spin_loop();
+        }
+    }
+    Ok(())
+}
+
+/// Wait for the STOP ending a transaction and acknowledge it.
+pub(crate) fn wait_stop(reg: &RegisterBlock, timeout: u32) -> Result<(), Error> {
+    // The transfer is complete, and every byte acknowledged, once STOP is sent.
+    wait_for(reg, timeout, |r| {
+        r.raw_intr_stat.read().is_stop_detect_pending()
+    })?;
+    end_transaction(reg);
+    Ok(())
+}
+
+/// Run a transaction whose read length is given by its first byte read.
+fn read_counted(
+    reg: &RegisterBlock,
+    timeout: u32,
+    header: &[u8],
+    extra: usize,
+    buffer: &mut [u8],
+) -> Result<Option<usize>, Error> {
+    write_operation(reg, timeout, header, false)?;
+    read_operation(reg, timeout, &mut buffer[..1], false)?;
+    let len = 1 + buffer[0] as usize + extra;
+    // A read command carrying the STOP must remain.
+    if len == 1 || len > buffer.len() {
+        return Ok(None);
+    }
+    read_operation(reg, timeout, &mut buffer[1..len], true)?;
+    wait_stop(reg, timeout)?;
+    Ok(Some(len))
+}
+
+/// Acknowledge the STOP ending a transaction.
+#[inline]
+pub(crate) fn end_transaction(reg: &RegisterBlock) {
+    unsafe {
+        reg.intr_clear
+            .write(InterruptClear::default().clear_stop_detect());
+    }
+}
+
+/// Fail if the controller aborted the transfer or SCL is stuck low.
+#[inline]
+pub(crate) fn check_abort(reg: &RegisterBlock) -> Result<(), Error> {
+    let raw = reg.raw_intr_stat.read();
+    if raw.is_tx_abort_pending() {
+        Err(Error::from_abort_source(reg.tx_abrt_source.read()))
+    } else if raw.is_scl_stuck_at_low_pending() {
+        Err(Error::SclStuckLow)
+    } else {
+        Ok(())
+    }
+}
+
+/// Queue `byte` for writing, followed by a STOP if `stop`.
+#[inline]
+pub(crate) fn push_write(reg: &RegisterBlock, byte: u8, stop: bool) {
+    unsafe {
+        // I2C_DATA_CMD[8]=0 (write), [7:0]=data, [9]=STOP
+        reg.data_cmd.write(
+            DataCommand::default()
+                .set_data_byte(byte)
+                .set_transfer_mode(TransferMode::Write)
+                .set_stop(stop),
+        );
+    }
+}
+
+/// Progress of a read operation.
+///
+/// Read commands and received data are interleaved so the receive FIFO
+/// never overflows.
+pub(crate) struct ReadProgress {
+    issued: usize,
+    received: usize,
+    stop: bool,
+}
+
+impl ReadProgress {
+    /// Start a read operation, followed by a STOP if `stop`.
+    #[inline]
+    pub(crate) const fn new(stop: bool) -> Self {
+        Self {
+            issued: 0,
+            received: 0,
+            stop,
+        }
+    }
+
+    /// Check if all of `buffer` has been received.
+    #[inline]
+    pub(crate) const fn is_done(&self, buffer: &[u8]) -> bool {
+        self.received == buffer.len()
+    }
+
+    /// Check if read commands remain that the receive FIFO has room for.
+    #[inline]
+    pub(crate) const fn can_issue(&self, buffer: &[u8]) -> bool {
+        self.issued < buffer.len() && self.issued - self.received < FIFO_DEPTH
+    }
+
+    /// Move received data into `buffer` and issue read commands while the
+    /// FIFOs allow, returning whether anything was done.
+    pub(crate) fn step(&mut self, reg: &RegisterBlock, buffer: &mut [u8]) -> bool {
+        let mut progress = false;
+        while self.received < buffer.len() && reg.rx_flr.read().rx_fifo_count() > 0 {
+            buffer[self.received] = reg.data_cmd.read().data_byte();
+            self.received += 1;
+            progress = true;
+        }
+        while self.can_issue(buffer) && reg.status.read().is_tx_fifo_not_full() {
+            let stop = self.stop && self.issued == buffer.len() - 1;
+            unsafe {
+                // I2C_DATA_CMD[8]=1 (read), [9]=STOP
+                reg.data_cmd.write(
+                    DataCommand::default()
+                        .set_transfer_mode(TransferMode::Read)
+                        .set_stop(stop),
+                );
             }
+            self.issued += 1;
+            progress = true;
+        }
+        progress
+    }
+}
+
+/// Wait until `cond` holds, failing on a transmit abort or after `timeout` polls.
+pub(crate) fn wait_for(
+    reg: &RegisterBlock,
+    timeout: u32,
+    mut cond: impl FnMut(&RegisterBlock) -> bool,
+) -> Result<(), Error> {
+    let mut polls = 0;
+    loop {
+        check_abort(reg)?;
+        if cond(reg) {
+            return Ok(());
+        }
+        polls += 1;
+        if polls >= timeout {
+            return Err(Error::Timeout);
+        }
+        core::hint::spin_loop();
+    }
+}
+
+/// Spin until `cond` holds or `timeout` polls have passed, returning whether it holds.
+#[inline]
+fn spin_until(reg: &RegisterBlock, timeout: u32, cond: impl Fn(&RegisterBlock) -> bool) -> bool {
+    (0..timeout).any(|_| {
+        core::hint::spin_loop();
+        cond(reg)
+    })
+}
+
+/// Return the controller to idle after a failed transfer.
+///
+/// A transfer still in progress is aborted with a STOP. Clearing the abort
+/// releases the flushed transmit FIFO, and stale received data is discarded.
+/// If SDA is stuck low, the bus is cleared with [`recover_bus`]. If the
+/// controller stays busy, e.g. with SCL stuck low, it is disabled and enabled
+/// again.
+pub(crate) fn recover(reg: &RegisterBlock, timeout: u32) {
+    let status = reg.status.read();
+    if status.is_master_active() || !status.is_tx_fifo_empty() {
+        unsafe { reg.enable.modify(|v| v.set_abort(true)) };
+        spin_until(reg, timeout, |r| !r.enable.read().abort());
+    }
+    // The abort source is cleared together with the abort.
+    let sda_stuck = reg.tx_abrt_source.read().is_abrt_sda_stuck_at_low();
+    unsafe {
+        reg.intr_clear.write(
+            InterruptClear::default()
+                .clear_tx_abort()
+                .clear_stop_detect()
+                .clear_scl_stuck_at_low(),
+        )
+    };
+    while reg.rx_flr.read().rx_fifo_count() > 0 {
+        reg.data_cmd.read();
+    }
+    if sda_stuck {
+        recover_bus(reg, timeout).ok();
+    }
+    if !spin_until(reg, timeout, |r| !r.status.read().is_master_active()) {
+        unsafe {
+            reg.enable.modify(|v| v.disable_i2c());
+            spin_until(reg, timeout, |r| !r.enable_status.read().is_i2c_enabled());
+            reg.enable.modify(|v| v.enable_i2c());
         }
+    }
+}
 
+/// Release SDA held low by a target.
+///
+/// The controller clocks up to nine SCL pulses until the target releases SDA,
+/// then issues a STOP.
+pub(crate) fn recover_bus(reg: &RegisterBlock, timeout: u32) -> Result<(), Error> {
+    unsafe { reg.enable.modify(|v| v.enable_sda_stuck_recovery()) };
+    if !spin_until(reg, timeout, |r| {
+        !r.enable.read().is_sda_stuck_recovery_enabled()
+    }) {
+        return Err(Error::Timeout);
+    }
+    if reg.status.read().is_sda_stuck_not_recovered() {
+        Err(Error::SdaStuckLow)
+    } else {
         Ok(())
     }
 }
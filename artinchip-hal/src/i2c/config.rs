@@ -1,6 +1,7 @@
 //! I2C configuration.
 
-use super::register::SpeedMode;
+use embedded_time::duration::{Milliseconds, Nanoseconds};
+use embedded_time::rate::Hertz;
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 #[repr(u8)]
@@ -11,14 +12,30 @@ pub enum Role {
 
 pub struct I2cConfig {
     pub role: Role,
-    pub speed_mode: SpeedMode,
+    /// SCL frequency, up to 1 MHz (Fast-mode Plus).
+    ///
+    /// The achieved frequency never exceeds it and depends on the rise and
+    /// fall times.
+    pub freq: Hertz,
+    /// SCL and SDA rise time of the bus, as set by pull-ups and capacitance.
+    pub rise_time: Nanoseconds,
+    /// SCL and SDA fall time of the bus.
+    pub fall_time: Nanoseconds,
+    /// Status polls before a busy-wait fails with [`Error::Timeout`](super::Error::Timeout).
+    pub timeout: u32,
+    /// Time SCL or SDA may be held low before the bus is reported stuck; `None` disables detection.
+    pub stuck_timeout: Option<Milliseconds<u32>>,
 }
 
 impl Default for I2cConfig {
     fn default() -> Self {
         Self {
             role: Role::Master,
-            speed_mode: SpeedMode::Fast,
+            freq: Hertz(400_000),
+            rise_time: Nanoseconds(300),
+            fall_time: Nanoseconds(300),
+            timeout: 1_000_000,
+            stuck_timeout: Some(Milliseconds(25)),
         }
     }
 }
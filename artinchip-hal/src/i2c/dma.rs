@@ -0,0 +1,498 @@
+//! I2C DMA transfers.
+//!
+//! One channel feeds `I2C_DATA_CMD` from a buffer of command words holding
+//! the data to write, the read commands and the final STOP; a second channel
+//! drains received data. Buffers and task descriptors are written back from
+//! or invalidated in the data cache by these functions. Receive buffers must
+//! start and end on a cache line boundary, otherwise [`Error::Alignment`] is
+//! returned, so that invalidating them cannot discard neighbouring data.
+
+use super::blocking::{BlockingI2c, check_abort, recover, start_transaction, wait_stop};
+use super::error::Error;
+use super::pad::I2cPads;
+use super::register::{DataCommand, RegisterBlock, TransferMode};
+use crate::dma::cache;
+use crate::dma::{BurstSize, ChConfig, ChMode, DataWidth, DmaChannel, DmaTask, HandshakeMode};
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::blocking::end_transaction,
+    super::instance::{I2c, I2cInterrupt},
+    super::non_blocking::{AsyncI2c, AsyncState, RecoverOnDrop, wait_until},
+    super::register::InterruptMask,
+    crate::dma::DmaEvents,
+    core::future::{Future, poll_fn},
+    core::pin::pin,
+    core::task::Poll,
+};
+
+/// DMA device ID of SRAM.
+const DMA_ID_SRAM: u8 = 0;
+/// Transmit FIFO level at or below which command words are requested.
+const DMA_TX_LEVEL: u8 = 4;
+
+/// DMA handshake device ID of I2C instance `I` (`DMA_ID_I2C0` + `I`).
+#[inline]
+pub const fn i2c_dma_id<const I: u8>() -> u8 {
+    26 + I
+}
+
+#[inline]
+fn data_register(reg: &RegisterBlock) -> u32 {
+    &reg.data_cmd as *const _ as u32
+}
+
+/// Fill `cmds` with the command words writing `write`, then reading
+/// `read_len` bytes after a repeated START, and return the used part.
+fn fill_commands<'c>(
+    cmds: &'c mut [DataCommand],
+    write: &[u8],
+    read_len: usize,
+) -> &'c [DataCommand] {
+    let len = write.len() + read_len;
+    assert!(
+        cmds.len() >= len,
+        "Command buffer too short (expected at least one word per byte)"
+    );
+    let cmds = &mut cmds[..len];
+    let (write_cmds, read_cmds) = cmds.split_at_mut(write.len());
+    for (cmd, &byte) in write_cmds.iter_mut().zip(write) {
+        *cmd = DataCommand::default()
+            .set_data_byte(byte)
+            .set_transfer_mode(TransferMode::Write);
+    }
+    read_cmds.fill(DataCommand::default().set_transfer_mode(TransferMode::Read));
+    cmds[len - 1] = cmds[len - 1].set_stop(true);
+    cmds
+}
+
+#[inline]
+fn enable_dma_handshake(reg: &RegisterBlock, rx: bool) {
+    unsafe {
+        reg.dma_tx_level.modify(|v| v.set_tx_level(DMA_TX_LEVEL));
+        // Request every received byte.
+        reg.dma_rx_level.modify(|v| v.set_rx_level(0));
+        reg.dma_ctrl.modify(|v| {
+            let v = v.enable_tx_dma();
+            if rx { v.enable_rx_dma() } else { v }
+        });
+    }
+}
+
+#[inline]
+fn disable_dma_handshake(reg: &RegisterBlock) {
+    unsafe { reg.dma_ctrl.modify(|v| v.disable_tx_dma().disable_rx_dma()) };
+}
+
+/// Reject receive buffers that share a cache line with other data.
+#[inline]
+fn check_rx_buffer(buf: &[u8]) -> Result<(), Error> {
+    if cache::is_line_aligned(buf.as_ptr() as usize, buf.len()) {
+        Ok(())
+    } else {
+        Err(Error::Alignment)
+    }
+}
+
+/// Build a command buffer to I2C transmit task.
+fn tx_task<const I: u8>(reg: &RegisterBlock, cmds: &[DataCommand]) -> DmaTask {
+    DmaTask {
+        cfg: ChConfig::zeroed()
+            .set_src_dev(DMA_ID_SRAM)
+            .set_src_data_width(DataWidth::Bits32)
+            .set_src_burst(BurstSize::Burst1)
+            .enable_src_addr_inc()
+            .set_snk_dev(i2c_dma_id::<I>())
+            .set_snk_data_width(DataWidth::Bits32)
+            .set_snk_burst(BurstSize::Burst1)
+            .disable_snk_addr_inc(),
+        src: cmds.as_ptr() as u32,
+        dst: data_register(reg),
+        len: size_of_val(cmds) as u32,
+        delay: DmaTask::DEFAULT_DELAY,
+        p_next: DmaTask::TASK_END,
+        mode: ChMode::zeroed()
+            .set_src_mode(HandshakeMode::Wait)
+            .set_snk_mode(HandshakeMode::Handshake),
+        v_next: None,
+    }
+}
+
+/// Build an I2C to memory receive task.
+fn rx_task<const I: u8>(reg: &RegisterBlock, buf: &mut [u8]) -> DmaTask {
+    DmaTask {
+        cfg: ChConfig::zeroed()
+            .set_src_dev(i2c_dma_id::<I>())
+            .set_src_data_width(DataWidth::Bits8)
+            .set_src_burst(BurstSize::Burst1)
+            .disable_src_addr_inc()
+            .set_snk_dev(DMA_ID_SRAM)
+            .set_snk_data_width(DataWidth::Bits8)
+            .set_snk_burst(BurstSize::Burst1)
+            .enable_snk_addr_inc(),
+        src: data_register(reg),
+        dst: buf.as_mut_ptr() as u32,
+        len: buf.len() as u32,
+        delay: DmaTask::DEFAULT_DELAY,
+        p_next: DmaTask::TASK_END,
+        mode: ChMode::zeroed()
+            .set_src_mode(HandshakeMode::Handshake)
+            .set_snk_mode(HandshakeMode::Wait),
+        v_next: None,
+    }
+}
+
+#[inline]
+fn start_task<const C: u8>(ch: &mut DmaChannel<'_, C>, task: &DmaTask) {
+    cache::clean_invalidate(task as *const _ as usize, size_of::<DmaTask>());
+    ch.start(task);
+}
+
+/// Wait until channel `ch` finished, failing on a transmit abort, a DMA
+/// error or after `timeout` polls.
+fn blocking_wait<const C: u8>(
+    reg: &RegisterBlock,
+    ch: &mut DmaChannel<'_, C>,
+    timeout: u32,
+) -> Result<(), Error> {
+    let mut spins = 0;
+    let result = loop {
+        if ch.is_addr_req_error_pending() {
+            break Err(Error::Dma);
+        }
+        if ch.is_all_finish_pending() {
+            break Ok(());
+        }
+        if let Err(e) = check_abort(reg) {
+            break Err(e);
+        }
+        spins += 1;
+        if spins >= timeout {
+            break Err(Error::Timeout);
+        }
+        core::hint::spin_loop();
+    };
+    ch.clear_all_pending();
+    result
+}
+
+impl<'a, const I: u8, PAD> BlockingI2c<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+{
+    /// Write `data` to `address` through DMA channel `ch`.
+    ///
+    /// `cmds` receives the generated command words and holds at least one
+    /// word per byte. Polls the channel status; do not bind
+    /// `DmaInterruptHandler` while using the blocking DMA functions.
+    pub fn write_dma<const C: u8>(
+        &mut self,
+        ch: &mut DmaChannel<'_, C>,
+        address: u16,
+        data: &[u8],
+        cmds: &mut [DataCommand],
+    ) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::Protocol);
+        }
+        let cmds = fill_commands(cmds, data, 0);
+        start_transaction(self.reg, address);
+        enable_dma_handshake(self.reg, false);
+        cache::clean_invalidate(cmds.as_ptr() as usize, size_of_val(cmds));
+        let task = tx_task::<I>(self.reg, cmds);
+        start_task(ch, &task);
+        let result = blocking_wait(self.reg, ch, self.timeout)
+            .and_then(|()| wait_stop(self.reg, self.timeout));
+        disable_dma_handshake(self.reg);
+        if result.is_err() {
+            ch.stop();
+            recover(self.reg, self.timeout);
+        }
+        result
+    }
+
+    /// Write `write` to `address`, then read `read` after a repeated START,
+    /// through DMA channels `tx` and `rx`.
+    ///
+    /// `write` may be empty. `cmds` receives the generated command words and
+    /// holds at least one word per byte written or read. `read` must be cache
+    /// line aligned and sized, otherwise [`Error::Alignment`] is returned.
+    pub fn write_read_dma<const T: u8, const R: u8>(
+        &mut self,
+        tx: &mut DmaChannel<'_, T>,
+        rx: &mut DmaChannel<'_, R>,
+        address: u16,
+        write: &[u8],
+        read: &mut [u8],
+        cmds: &mut [DataCommand],
+    ) -> Result<(), Error> {
+        if read.is_empty() {
+            return self.write_dma(tx, address, write, cmds);
+        }
+        check_rx_buffer(read)?;
+        let cmds = fill_commands(cmds, write, read.len());
+        start_transaction(self.reg, address);
+        enable_dma_handshake(self.reg, true);
+        cache::clean_invalidate(cmds.as_ptr() as usize, size_of_val(cmds));
+        cache::clean_invalidate(read.as_ptr() as usize, read.len());
+        let read_task = rx_task::<I>(self.reg, read);
+        start_task(rx, &read_task);
+        let cmd_task = tx_task::<I>(self.reg, cmds);
+        start_task(tx, &cmd_task);
+        // All commands are issued once the last byte is received.
+        let result = blocking_wait(self.reg, rx, self.timeout)
+            .and_then(|()| wait_stop(self.reg, self.timeout));
+        disable_dma_handshake(self.reg);
+        if result.is_err() {
+            tx.stop();
+            rx.stop();
+            recover(self.reg, self.timeout);
+        }
+        tx.clear_all_pending();
+        cache::invalidate(read.as_ptr() as usize, read.len());
+        result
+    }
+
+    /// Read `buf` from `address` through DMA channels `tx` and `rx`.
+    #[inline]
+    pub fn read_dma<const T: u8, const R: u8>(
+        &mut self,
+        tx: &mut DmaChannel<'_, T>,
+        rx: &mut DmaChannel<'_, R>,
+        address: u16,
+        buf: &mut [u8],
+        cmds: &mut [DataCommand],
+    ) -> Result<(), Error> {
+        self.write_read_dma(tx, rx, address, &[], buf, cmds)
+    }
+}
+
+/// Stops the channel if a transfer future is dropped before completion.
+#[cfg(feature = "clic_interrupts")]
+struct StopOnDrop<'c, 'd, const C: u8> {
+    ch: &'c mut DmaChannel<'d, C>,
+    done: bool,
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'c, 'd, const C: u8> Drop for StopOnDrop<'c, 'd, C> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.ch.stop();
+        }
+    }
+}
+
+/// Disables the DMA handshake when a transfer ends or its future is dropped.
+#[cfg(feature = "clic_interrupts")]
+struct DisableDmaOnDrop<'r> {
+    reg: &'r RegisterBlock,
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl Drop for DisableDmaOnDrop<'_> {
+    fn drop(&mut self) {
+        disable_dma_handshake(self.reg);
+    }
+}
+
+/// Wait until channel `ch` finished, failing on a transmit abort, SCL
+/// stuck low or a DMA error.
+#[cfg(feature = "clic_interrupts")]
+async fn async_wait<const C: u8>(
+    reg: &RegisterBlock,
+    state: &AsyncState,
+    ch: &mut DmaChannel<'_, C>,
+) -> Result<(), Error> {
+    let mut finish =
+        pin!(ch.wait_for_events(DmaEvents::ALL_FINISH.union(DmaEvents::ADDR_REQ_ERROR)));
+    poll_fn(|cx| {
+        if let Poll::Ready(events) = finish.as_mut().poll(cx) {
+            if events.intersects(DmaEvents::ADDR_REQ_ERROR) {
+                return Poll::Ready(Err(Error::Dma));
+            }
+            return Poll::Ready(Ok(()));
+        }
+        state.waker.register(cx.waker());
+        if let Err(e) = check_abort(reg) {
+            return Poll::Ready(Err(e));
+        }
+        unsafe {
+            reg.intr_mask
+                .modify(|v| v.enable_tx_abort().enable_scl_stuck_at_low())
+        };
+        Poll::Pending
+    })
+    .await
+}
+
+/// Wait for the STOP ending a DMA transfer.
+#[cfg(feature = "clic_interrupts")]
+async fn async_wait_stop(reg: &RegisterBlock, state: &AsyncState) -> Result<(), Error> {
+    wait_until(reg, state, InterruptMask::enable_stop_detect, |r| {
+        r.raw_intr_stat.read().is_stop_detect_pending()
+    })
+    .await?;
+    end_transaction(reg);
+    Ok(())
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'a, const I: u8, PAD> AsyncI2c<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    /// Write `data` to `address` through DMA channel `ch`.
+    ///
+    /// `cmds` receives the generated command words and holds at least one
+    /// word per byte. Completion is reported by the channel finish interrupt;
+    /// requires `DmaInterruptHandler` bound to the `DMA` interrupt.
+    pub async fn write_dma<const C: u8>(
+        &mut self,
+        ch: &mut DmaChannel<'_, C>,
+        address: u16,
+        data: &[u8],
+        cmds: &mut [DataCommand],
+    ) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::Protocol);
+        }
+        let reg = self.reg;
+        let state = <I2c<I> as I2cInterrupt<I>>::state();
+        let cmds = fill_commands(cmds, data, 0);
+        start_transaction(reg, address);
+        let mut guard = RecoverOnDrop {
+            reg,
+            timeout: self.timeout,
+            armed: true,
+        };
+        enable_dma_handshake(reg, false);
+        let _dma = DisableDmaOnDrop { reg };
+        cache::clean_invalidate(cmds.as_ptr() as usize, size_of_val(cmds));
+        let task = tx_task::<I>(reg, cmds);
+        ch.take_events();
+        start_task(ch, &task);
+        let mut tx = StopOnDrop { ch, done: false };
+        let result = match async_wait(reg, state, tx.ch).await {
+            Ok(()) => async_wait_stop(reg, state).await,
+            Err(e) => Err(e),
+        };
+        tx.done = result.is_ok();
+        guard.armed = result.is_err();
+        result
+    }
+
+    /// Write `write` to `address`, then read `read` after a repeated START,
+    /// through DMA channels `tx` and `rx`.
+    ///
+    /// `write` may be empty. `cmds` receives the generated command words and
+    /// holds at least one word per byte written or read. `read` must be cache
+    /// line aligned and sized, otherwise [`Error::Alignment`] is returned.
+    pub async fn write_read_dma<const T: u8, const R: u8>(
+        &mut self,
+        tx: &mut DmaChannel<'_, T>,
+        rx: &mut DmaChannel<'_, R>,
+        address: u16,
+        write: &[u8],
+        read: &mut [u8],
+        cmds: &mut [DataCommand],
+    ) -> Result<(), Error> {
+        if read.is_empty() {
+            return self.write_dma(tx, address, write, cmds).await;
+        }
+        check_rx_buffer(read)?;
+        let reg = self.reg;
+        let state = <I2c<I> as I2cInterrupt<I>>::state();
+        let cmds = fill_commands(cmds, write, read.len());
+        start_transaction(reg, address);
+        let mut guard = RecoverOnDrop {
+            reg,
+            timeout: self.timeout,
+            armed: true,
+        };
+        enable_dma_handshake(reg, true);
+        let _dma = DisableDmaOnDrop { reg };
+        cache::clean_invalidate(cmds.as_ptr() as usize, size_of_val(cmds));
+        cache::clean_invalidate(read.as_ptr() as usize, read.len());
+        let read_task = rx_task::<I>(reg, read);
+        let cmd_task = tx_task::<I>(reg, cmds);
+        rx.take_events();
+        tx.take_events();
+        start_task(rx, &read_task);
+        let mut rx = StopOnDrop {
+            ch: rx,
+            done: false,
+        };
+        start_task(tx, &cmd_task);
+        let mut tx = StopOnDrop {
+            ch: tx,
+            done: false,
+        };
+        // All commands are issued once the last byte is received.
+        let result = match async_wait(reg, state, rx.ch).await {
+            Ok(()) => async_wait_stop(reg, state).await,
+            Err(e) => Err(e),
+        };
+        tx.done = result.is_ok();
+        rx.done = result.is_ok();
+        tx.ch.take_events();
+        guard.armed = result.is_err();
+        cache::invalidate(read.as_ptr() as usize, read.len());
+        result
+    }
+
+    /// Read `buf` from `address` through DMA channels `tx` and `rx`.
+    #[inline]
+    pub async fn read_dma<const T: u8, const R: u8>(
+        &mut self,
+        tx: &mut DmaChannel<'_, T>,
+        rx: &mut DmaChannel<'_, R>,
+        address: u16,
+        buf: &mut [u8],
+        cmds: &mut [DataCommand],
+    ) -> Result<(), Error> {
+        self.write_read_dma(tx, rx, address, &[], buf, cmds).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataCommand, TransferMode, fill_commands};
+
+    fn write(byte: u8) -> DataCommand {
+        DataCommand::default()
+            .set_data_byte(byte)
+            .set_transfer_mode(TransferMode::Write)
+    }
+
+    fn read() -> DataCommand {
+        DataCommand::default().set_transfer_mode(TransferMode::Read)
+    }
+
+    #[test]
+    fn fill_commands_write_read() {
+        let mut cmds = [DataCommand::default(); 6];
+        assert_eq!(
+            fill_commands(&mut cmds, &[0x12, 0x34], 2),
+            &[write(0x12), write(0x34), read(), read().set_stop(true)]
+        );
+    }
+
+    #[test]
+    fn fill_commands_write_only() {
+        let mut cmds = [DataCommand::default(); 2];
+        assert_eq!(
+            fill_commands(&mut cmds, &[0x12, 0x34], 0),
+            &[write(0x12), write(0x34).set_stop(true)]
+        );
+    }
+
+    #[test]
+    fn fill_commands_read_only() {
+        let mut cmds = [DataCommand::default(); 3];
+        assert_eq!(
+            fill_commands(&mut cmds, &[], 3),
+            &[read(), read(), read().set_stop(true)]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! I2C errors.
+
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+use super::register::TxAbortSource;
+
+/// I2C transfer error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// The target did not acknowledge its address.
+    AddressNack,
+    /// The target did not acknowledge a data byte.
+    DataNack,
+    /// No target acknowledged a general call, or a general call was followed by a read.
+    GeneralCall,
+    /// Another controller won arbitration.
+    ArbitrationLoss,
+    /// SDA was held low by a target and could not be released.
+    SdaStuckLow,
+    /// SCL was held low by a target.
+    SclStuckLow,
+    /// The transfer was aborted by software.
+    Aborted,
+    /// Invalid command sequence, such as a START byte, a 10-bit read with restart
+    /// disabled, or a transaction without data.
+    Protocol,
+    /// A busy-wait exceeded the configured timeout.
+    Timeout,
+    /// DMA address or request error.
+    Dma,
+    /// DMA receive buffer does not start and end on a cache line boundary.
+    Alignment,
+}
+
+impl Error {
+    /// Map the sources of a transmit abort to an error.
+    pub(crate) const fn from_abort_source(source: TxAbortSource) -> Self {
+        if source.is_abrt_sda_stuck_at_low() {
+            Error::SdaStuckLow
+        } else if source.is_abrt_lost() || source.is_abrt_slv_arb_lost() {
+            Error::ArbitrationLoss
+        } else if source.is_abrt_7b_addr_noack()
+            || source.is_abrt_10addr1_noack()
+            || source.is_abrt_10addr2_noack()
+        {
+            Error::AddressNack
+        } else if source.is_abrt_txdata_noack() {
+            Error::DataNack
+        } else if source.is_abrt_gcall_noack() || source.is_abrt_gcall_read() {
+            Error::GeneralCall
+        } else if source.is_abrt_user_abrt() {
+            Error::Aborted
+        } else {
+            Error::Protocol
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::AddressNack => f.write_str("address not acknowledged"),
+            Error::DataNack => f.write_str("data not acknowledged"),
+            Error::GeneralCall => f.write_str("general call not acknowledged or followed by read"),
+            Error::ArbitrationLoss => f.write_str("arbitration lost"),
+            Error::SdaStuckLow => f.write_str("SDA stuck low"),
+            Error::SclStuckLow => f.write_str("SCL stuck low"),
+            Error::Aborted => f.write_str("transfer aborted"),
+            Error::Protocol => f.write_str("invalid command sequence"),
+            Error::Timeout => f.write_str("timeout"),
+            Error::Dma => f.write_str("DMA transfer error"),
+            Error::Alignment => f.write_str("DMA buffer not cache line aligned"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl embedded_hal::i2c::Error for Error {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::AddressNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            Error::DataNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            Error::GeneralCall => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown),
+            Error::ArbitrationLoss => ErrorKind::ArbitrationLoss,
+            Error::SdaStuckLow | Error::SclStuckLow => ErrorKind::Bus,
+            Error::Aborted | Error::Protocol | Error::Timeout | Error::Dma | Error::Alignment => {
+                ErrorKind::Other
+            }
+        }
+    }
+}
@@ -3,7 +3,13 @@
 use super::blocking::BlockingI2c;
 use super::config::I2cConfig;
 use super::pad::I2cPads;
+use super::target::I2cTarget;
 use crate::cmu::Cmu;
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::instance::*, super::non_blocking::*, super::target::AsyncI2cTarget,
+    crate::interrupt::clic::typelevel,
+};
 
 pub trait I2cExt<'a, const I: u8> {
     /// Creates a blocking I2C interface with the specified pads.
@@ -15,4 +21,49 @@ pub trait I2cExt<'a, const I: u8> {
     ) -> BlockingI2c<'a, I, PAD>
     where
         PAD: I2cPads<I>;
+    /// Creates an interrupt driven I2C controller with the specified pads.
+    ///
+    /// The role in `config` is ignored.
+    #[cfg(feature = "clic_interrupts")]
+    fn new_async<PAD, IRQS>(
+        self,
+        pad: PAD,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+        _irqs: IRQS,
+    ) -> AsyncI2c<'a, I, PAD>
+    where
+        PAD: I2cPads<I>,
+        I2c<I>: I2cInterrupt<I>,
+        AsyncI2cHandler<I>: typelevel::Handler<<I2c<I> as I2cInterrupt<I>>::Interrupt>,
+        IRQS: typelevel::Binding<<I2c<I> as I2cInterrupt<I>>::Interrupt, AsyncI2cHandler<I>>;
+    /// Creates a blocking I2C target responding to `address`.
+    ///
+    /// The role in `config` is ignored.
+    fn new_target<PAD>(
+        self,
+        pad: PAD,
+        address: u16,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+    ) -> I2cTarget<'a, I, PAD>
+    where
+        PAD: I2cPads<I>;
+    /// Creates an interrupt driven I2C target responding to `address`.
+    ///
+    /// The role in `config` is ignored.
+    #[cfg(feature = "clic_interrupts")]
+    fn new_async_target<PAD, IRQS>(
+        self,
+        pad: PAD,
+        address: u16,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+        _irqs: IRQS,
+    ) -> AsyncI2cTarget<'a, I, PAD>
+    where
+        PAD: I2cPads<I>,
+        I2c<I>: I2cInterrupt<I>,
+        AsyncI2cHandler<I>: typelevel::Handler<<I2c<I> as I2cInterrupt<I>>::Interrupt>,
+        IRQS: typelevel::Binding<<I2c<I> as I2cInterrupt<I>>::Interrupt, AsyncI2cHandler<I>>;
 }
@@ -5,8 +5,47 @@ use super::config::I2cConfig;
 use super::i2c_ext::I2cExt;
 use super::pad::I2cPads;
 use super::register::RegisterBlock;
+use super::target::I2cTarget;
 use crate::cmu::Cmu;
 use core::marker::PhantomData;
+#[cfg(feature = "clic_interrupts")]
+use {super::non_blocking::*, super::target::AsyncI2cTarget, crate::interrupt::clic::typelevel};
+
+/// Trait to map const generic I to its interrupt type (used for compile-time safety).
+#[cfg(feature = "clic_interrupts")]
+pub trait I2cInterrupt<const I: u8> {
+    type Interrupt: typelevel::Interrupt;
+    /// Async state of this instance.
+    fn state() -> &'static AsyncState;
+}
+
+// Macro to quickly map instance numbers to interrupt types
+#[cfg(feature = "clic_interrupts")]
+macro_rules! impl_i2c_interrupts {
+    ( $( ($inst:literal, $irq_type:ident) ),* $(,)? ) => {
+        $(
+            impl I2cInterrupt<$inst> for I2c<$inst> {
+                type Interrupt = crate::interrupt::clic::typelevel::$irq_type;
+                #[inline]
+                fn state() -> &'static AsyncState {
+                    static STATE: AsyncState = AsyncState::new();
+                    &STATE
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl_i2c_interrupts! {
+    (0, I2C0),
+    (1, I2C1),
+}
+#[cfg(feature = "clic_interrupts")]
+#[cfg(not(feature = "d12x"))]
+impl_i2c_interrupts! {
+    (2, I2C2),
+}
 
 /// I2C with statically known instance number.
 pub struct I2c<const I: u8> {
@@ -27,6 +66,15 @@ impl<const I: u8> I2c<I> {
     pub const fn register_block(&self) -> &'static RegisterBlock {
         unsafe { &*self.reg }
     }
+
+    /// Get register block for a specific index (used by Interrupt Handler).
+    #[cfg(feature = "clic_interrupts")]
+    #[inline(always)]
+    pub(crate) unsafe fn regs_at_index() -> &'static RegisterBlock {
+        let base_addr = 0x19220000 + (I as usize) * 0x1000;
+
+        unsafe { &*(base_addr as *const RegisterBlock) }
+    }
 }
 
 impl<const I: u8> I2cExt<'static, I> for I2c<I> {
@@ -41,4 +89,52 @@ impl<const I: u8> I2cExt<'static, I> for I2c<I> {
     {
         BlockingI2c::new(self.register_block(), pad, config, cmu)
     }
+    #[cfg(feature = "clic_interrupts")]
+    #[inline]
+    fn new_async<PAD, IRQS>(
+        self,
+        pad: PAD,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+        _irqs: IRQS,
+    ) -> AsyncI2c<'static, I, PAD>
+    where
+        PAD: I2cPads<I>,
+        I2c<I>: I2cInterrupt<I>,
+        AsyncI2cHandler<I>: typelevel::Handler<<I2c<I> as I2cInterrupt<I>>::Interrupt>,
+        IRQS: typelevel::Binding<<I2c<I> as I2cInterrupt<I>>::Interrupt, AsyncI2cHandler<I>>,
+    {
+        AsyncI2c::new(self.register_block(), pad, config, cmu)
+    }
+    #[inline]
+    fn new_target<PAD>(
+        self,
+        pad: PAD,
+        address: u16,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+    ) -> I2cTarget<'static, I, PAD>
+    where
+        PAD: I2cPads<I>,
+    {
+        I2cTarget::new(self.register_block(), pad, address, config, cmu)
+    }
+    #[cfg(feature = "clic_interrupts")]
+    #[inline]
+    fn new_async_target<PAD, IRQS>(
+        self,
+        pad: PAD,
+        address: u16,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+        _irqs: IRQS,
+    ) -> AsyncI2cTarget<'static, I, PAD>
+    where
+        PAD: I2cPads<I>,
+        I2c<I>: I2cInterrupt<I>,
+        AsyncI2cHandler<I>: typelevel::Handler<<I2c<I> as I2cInterrupt<I>>::Interrupt>,
+        IRQS: typelevel::Binding<<I2c<I> as I2cInterrupt<I>>::Interrupt, AsyncI2cHandler<I>>,
+    {
+        AsyncI2cTarget::new(self.register_block(), pad, address, config, cmu)
+    }
 }
@@ -0,0 +1,303 @@
+//! Interrupt driven I2C interface.
+
+use core::future::poll_fn;
+use core::task::Poll;
+
+use embassy_sync::waitqueue::AtomicWaker;
+use embedded_hal::i2c::{Operation, SevenBitAddress, TenBitAddress};
+use embedded_time::rate::Hertz;
+
+use super::blocking::{
+    ReadProgress, begin_transaction, check_abort, end_transaction, push_write, recover, recover_bus,
+};
+use super::config::{I2cConfig, Role};
+use super::error::Error;
+use super::instance::{I2c, I2cInterrupt};
+use super::pad::I2cPads;
+use super::register::{InterruptMask, RegisterBlock};
+use super::setup::{clock_register, init};
+use crate::cmu::Cmu;
+use crate::interrupt::clic::typelevel::{self, Interrupt as _};
+
+/// Async state of one I2C instance.
+pub struct AsyncState {
+    pub waker: AtomicWaker,
+}
+
+impl AsyncState {
+    pub const fn new() -> Self {
+        Self {
+            waker: AtomicWaker::new(),
+        }
+    }
+}
+
+impl Default for AsyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// I2C interrupt handler.
+///
+/// Masks all interrupts and wakes the waiting task, which checks the raw
+/// status and unmasks the interrupts it waits for again.
+pub struct AsyncI2cHandler<const I: u8>;
+
+impl<const I: u8> typelevel::Handler<<I2c<I> as I2cInterrupt<I>>::Interrupt> for AsyncI2cHandler<I>
+where
+    I2c<I>: I2cInterrupt<I>,
+{
+    unsafe fn on_interrupt() {
+        let reg = unsafe { I2c::<I>::regs_at_index() };
+        unsafe { reg.intr_mask.modify(|v| v.disable_all()) };
+        <I2c<I> as I2cInterrupt<I>>::state().waker.wake();
+        <I2c<I> as I2cInterrupt<I>>::Interrupt::clear_pending();
+    }
+}
+
+/// Wait until `poll` returns a value, unmasking the interrupts set by `unmask`
+/// while it does not.
+pub(crate) async fn wait_for_interrupt<T>(
+    reg: &RegisterBlock,
+    state: &AsyncState,
+    unmask: impl Fn(InterruptMask) -> InterruptMask,
+    mut poll: impl FnMut(&RegisterBlock) -> Option<T>,
+) -> T {
+    poll_fn(|cx| {
+        state.waker.register(cx.waker());
+        if let Some(value) = poll(reg) {
+            return Poll::Ready(value);
+        }
+        unsafe { reg.intr_mask.modify(&unmask) };
+        Poll::Pending
+    })
+    .await
+}
+
+/// Wait until `cond` holds, the transfer is aborted or SCL is stuck low.
+pub(crate) async fn wait_until(
+    reg: &RegisterBlock,
+    state: &AsyncState,
+    unmask: impl Fn(InterruptMask) -> InterruptMask,
+    cond: impl Fn(&RegisterBlock) -> bool,
+) -> Result<(), Error> {
+    wait_for_interrupt(
+        reg,
+        state,
+        |v| unmask(v).enable_tx_abort().enable_scl_stuck_at_low(),
+        |r| match check_abort(r) {
+            Err(e) => Some(Err(e)),
+            Ok(()) => cond(r).then_some(Ok(())),
+        },
+    )
+    .await
+}
+
+/// Returns the controller to idle if a transfer fails or its future is dropped.
+pub(crate) struct RecoverOnDrop<'r> {
+    pub(crate) reg: &'r RegisterBlock,
+    pub(crate) timeout: u32,
+    pub(crate) armed: bool,
+}
+
+impl Drop for RecoverOnDrop<'_> {
+    fn drop(&mut self) {
+        unsafe { self.reg.intr_mask.modify(|v| v.disable_all()) };
+        if self.armed {
+            recover(self.reg, self.timeout);
+        }
+    }
+}
+
+/// Interrupt driven I2C controller.
+///
+/// Transfers wait for interrupts without a time limit. A target holding SCL
+/// low is only detected with a `stuck_timeout` configured; with `None` such
+/// a transfer never completes, so bound it with a timer, e.g.
+/// `embassy_time::with_timeout`, which aborts it with a STOP when it expires.
+pub struct AsyncI2c<'a, const I: u8, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    pub(super) reg: &'a RegisterBlock,
+    pad: PAD,
+    pub(super) timeout: u32,
+    freq: Hertz,
+}
+
+impl<'a, const I: u8, PAD> AsyncI2c<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    // Refill the transmit FIFO once it is half empty.
+    const TX_THRESHOLD: u8 = 4;
+
+    /// Create a new async I2C controller.
+    ///
+    /// The role in `config` is ignored.
+    pub fn new(reg: &'a RegisterBlock, pad: PAD, config: I2cConfig, cmu: &mut Cmu) -> Self {
+        let config = I2cConfig {
+            role: Role::Master,
+            ..config
+        };
+        let freq = init::<I>(reg, &config, cmu);
+        unsafe {
+            reg.tx_tl.modify(|v| v.set_tx_threshold(Self::TX_THRESHOLD));
+            reg.rx_tl.modify(|v| v.set_rx_threshold(0));
+        }
+        Self {
+            reg,
+            pad,
+            timeout: config.timeout,
+            freq,
+        }
+    }
+
+    /// SCL frequency achieved for the configured frequency and bus timing.
+    #[inline]
+    pub fn freq(&self) -> Hertz {
+        self.freq
+    }
+
+    /// Run `operations` as one transaction with `address`.
+    ///
+    /// A transfer cancelled by dropping its future is aborted with a STOP.
+    /// Transactions that transfer no data fail with [`Error::Protocol`].
+    /// The waits are unbounded; see [`AsyncI2c`].
+    async fn transfer(
+        &mut self,
+        address: u16,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Error> {
+        let count = begin_transaction(self.reg, address, operations);
+        if count == 0 && !operations.is_empty() {
+            return Err(Error::Protocol);
+        }
+        let mut guard = RecoverOnDrop {
+            reg: self.reg,
+            timeout: self.timeout,
+            armed: true,
+        };
+        let result = self.transfer_operations(&mut operations[..count]).await;
+        guard.armed = result.is_err();
+        result
+    }
+
+    async fn transfer_operations(&mut self, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        let reg = self.reg;
+        let state = <I2c<I> as I2cInterrupt<I>>::state();
+        let total_ops = operations.len();
+        if total_ops == 0 {
+            return Ok(());
+        }
+
+        for (idx, operation) in operations.iter_mut().enumerate() {
+            let is_last_op = idx == total_ops - 1;
+
+            match operation {
+                Operation::Write(bytes) => {
+                    for (byte_idx, &byte) in bytes.iter().enumerate() {
+                        if !reg.status.read().is_tx_fifo_not_full() {
+                            wait_until(reg, state, InterruptMask::enable_tx_empty, |r| {
+                                r.status.read().is_tx_fifo_not_full()
+                            })
+                            .await?;
+                        }
+                        // Only send STOP on last byte of last operation
+                        push_write(reg, byte, is_last_op && byte_idx == bytes.len() - 1);
+                    }
+                }
+                Operation::Read(buffer) => {
+                    let mut read = ReadProgress::new(is_last_op);
+                    loop {
+                        check_abort(reg)?;
+                        read.step(reg, buffer);
+                        if read.is_done(buffer) {
+                            break;
+                        }
+                        let issue = read.can_issue(buffer);
+                        wait_until(
+                            reg,
+                            state,
+                            |v| {
+                                let v = v.enable_rx_full();
+                                if issue { v.enable_tx_empty() } else { v }
+                            },
+                            |r| {
+                                r.rx_flr.read().rx_fifo_count() > 0
+                                    || (issue && r.status.read().is_tx_fifo_not_full())
+                            },
+                        )
+                        .await?;
+                    }
+                }
+            }
+        }
+
+        // The transfer is complete, and every byte acknowledged, once STOP is sent.
+        wait_until(reg, state, InterruptMask::enable_stop_detect, |r| {
+            r.raw_intr_stat.read().is_stop_detect_pending()
+        })
+        .await?;
+        end_transaction(reg);
+        Ok(())
+    }
+
+    /// Clear a bus left with SDA held low, e.g. by a target reset mid-transfer.
+    ///
+    /// Failed transfers already do this when the controller detects SDA stuck low.
+    #[inline]
+    pub fn recover_bus(&mut self) -> Result<(), Error> {
+        recover_bus(self.reg, self.timeout)
+    }
+
+    /// Free the async I2C and return I2C instance, SCL and SDA pads.
+    pub fn free(self, cmu: &Cmu) -> (I2c<I>, PAD) {
+        unsafe {
+            self.reg.intr_mask.modify(|v| v.disable_all());
+            clock_register::<I>(cmu).modify(|v| v.disable_bus_clk().enable_module_reset());
+        }
+        (I2c::__new(self.reg), self.pad)
+    }
+}
+
+impl<'a, const I: u8, PAD> embedded_hal::i2c::ErrorType for AsyncI2c<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    type Error = Error;
+}
+
+impl<'a, const I: u8, PAD> embedded_hal_async::i2c::I2c<SevenBitAddress> for AsyncI2c<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    #[inline]
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.transfer(address as u16, operations).await
+    }
+}
+
+impl<'a, const I: u8, PAD> embedded_hal_async::i2c::I2c<TenBitAddress> for AsyncI2c<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    #[inline]
+    async fn transaction(
+        &mut self,
+        address: u16,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.transfer(address, operations).await
+    }
+}
@@ -61,7 +61,17 @@ pub struct RegisterBlock {
     /// I2C transport abort source register (`I2C_TX_ABRT_SOURCE`).
     #[doc(alias = "I2C_TX_ABRT_SOURCE")]
     pub tx_abrt_source: RO<TxAbortSource>,
-    _reserved2: [u8; 0x38],
+    _reserved2: [u8; 0x28],
+    /// I2C DMA control register (`I2C_DMA_CR`).
+    #[doc(alias = "I2C_DMA_CR")]
+    pub dma_ctrl: RW<DmaControl>,
+    /// I2C DMA transmit data level register (`I2C_DMA_TDLR`).
+    #[doc(alias = "I2C_DMA_TDLR")]
+    pub dma_tx_level: RW<DmaTxLevel>,
+    /// I2C DMA receive data level register (`I2C_DMA_RDLR`).
+    #[doc(alias = "I2C_DMA_RDLR")]
+    pub dma_rx_level: RW<DmaRxLevel>,
+    _reserved3: [u8; 0x4],
     /// I2C receive threshold register (`I2C_RX_TL`).
     #[doc(alias = "I2C_RX_TL")]
     pub rx_tl: RW<RxThreshold>,
@@ -80,11 +90,11 @@ pub struct RegisterBlock {
     /// I2C SDA stuck timeout register (`I2C_SDA_STUCK_TIMEOUT`).
     #[doc(alias = "I2C_SDA_STUCK_TIMEOUT")]
     pub sda_stuck_timeout: RW<u32>,
-    _reserved3: [u8; 0x8],
+    _reserved4: [u8; 0x8],
     /// I2C fast speed spike suppression length register (`I2C_FS_SPKLEN`).
     #[doc(alias = "I2C_FS_SPKLEN")]
     pub fs_spklen: RW<u32>,
-    _reserved4: [u8; 0x48],
+    _reserved5: [u8; 0x48],
     /// I2C version register (`VERSION`).
     #[doc(alias = "VERSION")]
     pub version: RO<u32>,
@@ -407,7 +417,7 @@ pub enum TransferMode {
 }
 
 /// I2C data command register.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 #[repr(transparent)]
 pub struct DataCommand(u32);
 
@@ -884,7 +894,7 @@ impl InterruptMask {
 }
 
 /// I2C interrupt clear register.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 #[repr(transparent)]
 pub struct InterruptClear(u32);
 
@@ -1404,6 +1414,93 @@ impl RxThreshold {
     }
 }
 
+/// I2C DMA control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DmaControl(u32);
+
+impl DmaControl {
+    const TDMAE: u32 = 0x1 << 1;
+    const RDMAE: u32 = 0x1;
+
+    /// Enable transmit DMA handshake (`TDMAE`).
+    #[doc(alias = "TDMAE")]
+    #[inline]
+    pub const fn enable_tx_dma(self) -> Self {
+        Self(self.0 | Self::TDMAE)
+    }
+    /// Disable transmit DMA handshake.
+    #[inline]
+    pub const fn disable_tx_dma(self) -> Self {
+        Self(self.0 & !Self::TDMAE)
+    }
+    /// Check if transmit DMA handshake is enabled.
+    #[inline]
+    pub const fn is_tx_dma_enabled(self) -> bool {
+        (self.0 & Self::TDMAE) != 0
+    }
+    /// Enable receive DMA handshake (`RDMAE`).
+    #[doc(alias = "RDMAE")]
+    #[inline]
+    pub const fn enable_rx_dma(self) -> Self {
+        Self(self.0 | Self::RDMAE)
+    }
+    /// Disable receive DMA handshake.
+    #[inline]
+    pub const fn disable_rx_dma(self) -> Self {
+        Self(self.0 & !Self::RDMAE)
+    }
+    /// Check if receive DMA handshake is enabled.
+    #[inline]
+    pub const fn is_rx_dma_enabled(self) -> bool {
+        (self.0 & Self::RDMAE) != 0
+    }
+}
+
+/// I2C DMA transmit data level register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DmaTxLevel(u32);
+
+impl DmaTxLevel {
+    const DMATDL: u32 = 0x7;
+
+    /// Set the transmit FIFO level at or below which DMA is requested (`DMATDL`).
+    #[doc(alias = "DMATDL")]
+    #[inline]
+    pub const fn set_tx_level(self, level: u8) -> Self {
+        assert!(level < 8, "Level out of range (expected 0..=7)");
+        Self((self.0 & !Self::DMATDL) | ((level as u32) & Self::DMATDL))
+    }
+    /// Get transmit DMA data level.
+    #[inline]
+    pub const fn tx_level(self) -> u8 {
+        (self.0 & Self::DMATDL) as u8
+    }
+}
+
+/// I2C DMA receive data level register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DmaRxLevel(u32);
+
+impl DmaRxLevel {
+    const DMARDL: u32 = 0x7;
+
+    /// Set the receive FIFO level above which DMA is requested (`DMARDL`).
+    #[doc(alias = "DMARDL")]
+    #[inline]
+    pub const fn set_rx_level(self, level: u8) -> Self {
+        assert!(level < 8, "Level out of range (expected 0..=7)");
+        Self((self.0 & !Self::DMARDL) | ((level as u32) & Self::DMARDL))
+    }
+    /// Get receive DMA data level.
+    #[inline]
+    pub const fn rx_level(self) -> u8 {
+        (self.0 & Self::DMARDL) as u8
+    }
+}
+
 /// I2C transport threshold register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -1459,10 +1556,11 @@ impl RxFifoLevel {
 #[cfg(test)]
 mod tests {
     use super::{
-        AckGenCall, AddressMode, Control, DataCommand, Enable, EnableStatus, FastSclHighCnt,
-        FastSclLowCnt, InterruptClear, InterruptMask, RawInterruptStatus, RegisterBlock,
-        RxFifoLevel, RxThreshold, SdaHold, SdaSetup, Slave, SpeedMode, StandardSclHighCnt,
-        StandardSclLowCnt, Status, Target, TransferMode, TxAbortSource, TxFifoLevel, TxThreshold,
+        AckGenCall, AddressMode, Control, DataCommand, DmaControl, DmaRxLevel, DmaTxLevel, Enable,
+        EnableStatus, FastSclHighCnt, FastSclLowCnt, InterruptClear, InterruptMask,
+        RawInterruptStatus, RegisterBlock, RxFifoLevel, RxThreshold, SdaHold, SdaSetup, Slave,
+        SpeedMode, StandardSclHighCnt, StandardSclLowCnt, Status, Target, TransferMode,
+        TxAbortSource, TxFifoLevel, TxThreshold,
     };
     use crate::test_should_panic;
     use core::mem::offset_of;
@@ -1487,6 +1585,9 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, enable_status), 0x4C);
         assert_eq!(offset_of!(RegisterBlock, status), 0x50);
         assert_eq!(offset_of!(RegisterBlock, tx_abrt_source), 0x54);
+        assert_eq!(offset_of!(RegisterBlock, dma_ctrl), 0x80);
+        assert_eq!(offset_of!(RegisterBlock, dma_tx_level), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, dma_rx_level), 0x88);
         assert_eq!(offset_of!(RegisterBlock, rx_tl), 0x90);
         assert_eq!(offset_of!(RegisterBlock, tx_tl), 0x94);
         assert_eq!(offset_of!(RegisterBlock, tx_flr), 0x98);
@@ -2046,6 +2147,44 @@ mod tests {
         "Threshold out of range (expected 0..=7)"
     ),);
 
+    #[test]
+    fn struct_dma_control_functions() {
+        let mut val = DmaControl(0x0);
+        val = val.enable_tx_dma();
+        assert!(val.is_tx_dma_enabled());
+        assert_eq!(val.0, 0x0000_0002);
+        val = val.enable_rx_dma();
+        assert!(val.is_rx_dma_enabled());
+        assert_eq!(val.0, 0x0000_0003);
+        val = val.disable_tx_dma().disable_rx_dma();
+        assert!(!val.is_tx_dma_enabled());
+        assert!(!val.is_rx_dma_enabled());
+        assert_eq!(val.0, 0x0000_0000);
+    }
+
+    #[test]
+    fn struct_dma_level_functions() {
+        let tx = DmaTxLevel(0x0).set_tx_level(0x4);
+        assert_eq!(tx.tx_level(), 0x4);
+        assert_eq!(tx.0, 0x0000_0004);
+        let rx = DmaRxLevel(0x0).set_rx_level(0x7);
+        assert_eq!(rx.rx_level(), 0x7);
+        assert_eq!(rx.0, 0x0000_0007);
+    }
+
+    test_should_panic!(
+        (
+            test_set_dma_tx_level_panic,
+            DmaTxLevel(0x0).set_tx_level(0x8),
+            "Level out of range (expected 0..=7)"
+        ),
+        (
+            test_set_dma_rx_level_panic,
+            DmaRxLevel(0x0).set_rx_level(0x8),
+            "Level out of range (expected 0..=7)"
+        ),
+    );
+
     #[test]
     fn struct_tx_threshold_functions() {
         let mut val = TxThreshold(0x0);
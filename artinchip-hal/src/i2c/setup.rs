@@ -0,0 +1,245 @@
+//! I2C clock and controller configuration.
+
+use embedded_time::rate::Hertz;
+use volatile_register::RW;
+
+use super::config::{I2cConfig, Role};
+use super::register::{AddressMode, RegisterBlock, SpeedMode};
+use crate::cmu::{Cmu, SimpleModule2Clock};
+
+// I2C fixed clock is 24Mhz.
+const I2C_DEFAULT_CLOCK: u32 = 24_000_000;
+
+/// Get the CMU clock register of I2C instance `I`.
+pub(crate) fn clock_register<const I: u8>(cmu: &Cmu) -> &'static RW<SimpleModule2Clock> {
+    let clk = cmu.register_block();
+    match I {
+        0 => &clk.clock_i2c0,
+        1 => &clk.clock_i2c1,
+        2 => &clk.clock_i2c2,
+        3 => &clk.clock_i2c3,
+        _ => panic!("Invalid I2C index"),
+    }
+}
+
+/// Enable the module clock of I2C instance `I` and configure the controller,
+/// returning the achieved SCL frequency.
+pub(crate) fn init<const I: u8>(reg: &RegisterBlock, config: &I2cConfig, cmu: &mut Cmu) -> Hertz {
+    let timing = calc_timing(I2C_DEFAULT_CLOCK, config);
+    // Reference: https://aicdoc.artinchip.com/topics/ic/i2c/i2c-programming-guide-d13x.html
+    let i2c_clk = clock_register::<I>(cmu);
+    unsafe {
+        // Initialize module clock.
+        // Reference: https://aicdoc.artinchip.com/topics/ic/cmu/cmu-function2-d13x.html#topic_yvp_f24_4bc__table_qb3_bn5_ydc
+        i2c_clk.modify(|v| v.enable_bus_clk());
+        i2c_clk.modify(|v| v.enable_module_reset());
+        riscv::asm::delay(500);
+        i2c_clk.modify(|v| v.disable_module_reset());
+
+        // Disable I2C module before configuration.
+        reg.enable.modify(|v| v.disable_i2c());
+
+        // Disable interrupts.
+        reg.intr_mask.modify(|v| v.disable_all());
+
+        // Configure SCL high and low counts.
+        match timing.speed_mode {
+            SpeedMode::Standard => {
+                reg.ss_scl_hcnt
+                    .modify(|v| v.set_scl_high_count(timing.scl_hcnt));
+                reg.ss_scl_lcnt
+                    .modify(|v| v.set_scl_low_count(timing.scl_lcnt));
+            }
+            SpeedMode::Fast => {
+                reg.fs_scl_hcnt
+                    .modify(|v| v.set_scl_high_count(timing.scl_hcnt));
+                reg.fs_scl_lcnt
+                    .modify(|v| v.set_scl_low_count(timing.scl_lcnt));
+            }
+        }
+        reg.fs_spklen.write(timing.spike_len as u32);
+
+        // Configure SDA hold and setup time.
+        reg.sda_hold
+            .modify(|v| v.set_sda_tx_hold(timing.sda_tx_hold).set_sda_rx_hold(0));
+        reg.sda_setup.modify(|v| v.set_sda_setup(timing.sda_setup));
+
+        // Configure stuck bus detection, in module clock cycles.
+        let stuck_cycles = config
+            .stuck_timeout
+            .map_or(u32::MAX, |t| t.0.saturating_mul(I2C_DEFAULT_CLOCK / 1000));
+        reg.scl_stuck_timeout.write(stuck_cycles);
+        reg.sda_stuck_timeout.write(stuck_cycles);
+
+        // Configure I2C role.
+        match config.role {
+            Role::Master => reg.ctrl.modify(|v| {
+                v.enable_master_mode()
+                    .disable_slave_mode()
+                    .set_speed_mode(timing.speed_mode)
+                    .enable_restart()
+                    .enable_bus_clear_feature()
+            }),
+            Role::Slave => reg.ctrl.modify(|v| {
+                v.enable_slave_mode()
+                    .disable_master_mode()
+                    .set_speed_mode(timing.speed_mode)
+                    .set_stop_detect_if_addressed(true)
+            }),
+        }
+
+        // Enable I2C.
+        reg.enable.modify(|v| v.enable_i2c());
+    }
+    Hertz(timing.freq)
+}
+
+/// Set the target address in master mode, or the own address in slave mode.
+pub(crate) fn set_address(reg: &RegisterBlock, address: u16) {
+    // Disable I2C.
+    unsafe {
+        reg.enable.modify(|v| v.disable_i2c());
+    }
+
+    let addr_mode = if address > 0x7F {
+        AddressMode::Bit10
+    } else {
+        AddressMode::Bit7
+    };
+    unsafe {
+        if reg.ctrl.read().is_master_mode_enabled() {
+            reg.ctrl.modify(|v| v.set_address_mode_master(addr_mode));
+            reg.target.modify(|v| v.set_target_address(address));
+        } else {
+            reg.ctrl.modify(|v| v.set_address_mode_slave(addr_mode));
+            reg.slave_addr.modify(|v| v.set_slave_address(address));
+        }
+    }
+
+    // Enable I2C.
+    unsafe {
+        reg.enable.modify(|v| v.enable_i2c());
+    }
+}
+
+/// Bus timing register values.
+struct Timing {
+    speed_mode: SpeedMode,
+    scl_hcnt: u16,
+    scl_lcnt: u16,
+    spike_len: u8,
+    sda_tx_hold: u16,
+    sda_setup: u8,
+    /// Achieved SCL frequency in Hz.
+    freq: u32,
+}
+
+/// Calculate bus timing for module clock `clk` from the configured frequency
+/// and rise and fall times.
+///
+/// The SCL low phase covers tLOW plus the fall time and the high phase
+/// covers tHIGH; the rise time adds to the period. Time left over in the
+/// period is shared in proportion to the minimum phase lengths.
+fn calc_timing(clk: u32, config: &I2cConfig) -> Timing {
+    // Reference: I2C-bus specification (UM10204), tables 10 and 11.
+    const MAX_SPIKE_NS: u32 = 50; // Suppressed spike width.
+    const SDA_TX_HOLD_NS: u32 = 300; // Bridges the undefined region of the SCL fall.
+
+    let freq = config.freq.0;
+    assert!(
+        (1..=1_000_000).contains(&freq),
+        "I2C frequency out of range (expected 1..=1000000 Hz)"
+    );
+    // (speed mode, tLOW min, tHIGH min, tSU;DAT min) in ns.
+    let (speed_mode, low_ns, high_ns, setup_ns) = match freq {
+        ..=100_000 => (SpeedMode::Standard, 4700, 4000, 250),
+        ..=400_000 => (SpeedMode::Fast, 1300, 600, 100),
+        _ => (SpeedMode::Fast, 500, 260, 50),
+    };
+    let cycles = |ns: u32| (ns as u64 * clk as u64).div_ceil(1_000_000_000) as u32;
+    let rise = config.rise_time.0;
+    let fall = config.fall_time.0;
+
+    let spike_len = cycles(MAX_SPIKE_NS).max(1);
+    // The controller stretches the phases by the spike suppression length;
+    // LCNT >= SPKLEN + 7 and HCNT >= SPKLEN + 5.
+    let low_min = cycles(low_ns + fall).max(spike_len + 8);
+    let high_min = cycles(high_ns).max(2 * spike_len + 12);
+    let rise_cycles = cycles(rise);
+    let spare = clk
+        .div_ceil(freq)
+        .saturating_sub(low_min + high_min + rise_cycles);
+    let low = low_min + (spare as u64 * low_ns as u64 / (low_ns + high_ns) as u64) as u32;
+    let high = high_min + spare - (low - low_min);
+
+    // Below about clk / 2^17 the counters saturate; report the rate they give.
+    let scl_lcnt = (low - 1).min(u16::MAX as u32);
+    let scl_hcnt = (high - spike_len - 7).min(u16::MAX as u32);
+    let period = (scl_lcnt + 1) + (scl_hcnt + spike_len + 7) + rise_cycles;
+
+    Timing {
+        speed_mode,
+        scl_lcnt: scl_lcnt as u16,
+        scl_hcnt: scl_hcnt as u16,
+        spike_len: spike_len.min(u8::MAX as u32) as u8,
+        sda_tx_hold: cycles(SDA_TX_HOLD_NS.max(fall)).min(scl_lcnt) as u16,
+        sda_setup: (cycles(setup_ns + rise) + 1).clamp(2, u8::MAX as u32) as u8,
+        freq: clk / period,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{I2C_DEFAULT_CLOCK, SpeedMode, calc_timing};
+    use crate::i2c::I2cConfig;
+    use embedded_time::duration::Nanoseconds;
+    use embedded_time::rate::Hertz;
+
+    fn config(freq: u32, edge_ns: u32) -> I2cConfig {
+        I2cConfig {
+            freq: Hertz(freq),
+            rise_time: Nanoseconds(edge_ns),
+            fall_time: Nanoseconds(edge_ns),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn calc_timing_standard_mode() {
+        let timing = calc_timing(I2C_DEFAULT_CLOCK, &config(100_000, 300));
+        assert_eq!(timing.speed_mode, SpeedMode::Standard);
+        assert_eq!(timing.scl_lcnt, 127);
+        assert_eq!(timing.scl_hcnt, 95);
+        assert_eq!(timing.spike_len, 2);
+        assert_eq!(timing.freq, 100_000);
+    }
+
+    #[test]
+    fn calc_timing_fast_mode() {
+        let timing = calc_timing(I2C_DEFAULT_CLOCK, &config(400_000, 120));
+        assert_eq!(timing.speed_mode, SpeedMode::Fast);
+        assert_eq!(timing.scl_lcnt, 38);
+        assert_eq!(timing.scl_hcnt, 9);
+        assert_eq!(timing.freq, 400_000);
+    }
+
+    #[test]
+    fn calc_timing_fast_mode_plus() {
+        // Minimum phase lengths and rise time limit the rate at 24 MHz.
+        let timing = calc_timing(I2C_DEFAULT_CLOCK, &config(1_000_000, 120));
+        assert_eq!(timing.speed_mode, SpeedMode::Fast);
+        assert_eq!(timing.scl_lcnt, 14);
+        assert_eq!(timing.scl_hcnt, 7);
+        assert_eq!(timing.freq, 705_882);
+    }
+
+    #[test]
+    fn calc_timing_clamped_counters() {
+        let timing = calc_timing(I2C_DEFAULT_CLOCK, &config(150, 300));
+        assert_eq!(timing.scl_lcnt, u16::MAX);
+        assert_eq!(timing.scl_hcnt, u16::MAX);
+        assert_eq!(timing.freq, 183);
+        let timing = calc_timing(I2C_DEFAULT_CLOCK, &config(1, 300));
+        assert_eq!(timing.freq, 183);
+    }
+}
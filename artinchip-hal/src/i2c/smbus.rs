@@ -0,0 +1,273 @@
+//! System Management Bus (SMBus) on top of a blocking I2C controller.
+//!
+//! PMBus devices are driven with the same transactions, e.g. `READ_VOUT` is
+//! a read word. Packet error checking (PEC) appends a CRC-8 over every byte
+//! of a transaction, addresses included. The controller can not address a
+//! target without transferring data, so the only quick command is
+//! [`Smbus::quick_read`], which reads one byte instead.
+
+use embedded_hal::i2c::{I2c as _, Operation};
+
+use super::blocking::BlockingI2c;
+use super::error::Error;
+use super::pad::I2cPads;
+
+/// General call address.
+const SMBUS_GENERAL_CALL: u8 = 0x00;
+/// Alert response address.
+const SMBUS_ALERT_RESPONSE: u8 = 0x0C;
+/// Maximum block length (SMBus 3.0).
+pub const SMBUS_BLOCK_MAX: usize = 255;
+
+/// SMBus error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SmbusError {
+    /// Transfer error.
+    I2c(Error),
+    /// Received PEC mismatch.
+    Pec,
+    /// The block length received is zero or exceeds the buffer.
+    BlockLength,
+}
+
+impl core::fmt::Display for SmbusError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SmbusError::I2c(e) => write!(f, "SMBus transfer error: {e}"),
+            SmbusError::Pec => f.write_str("SMBus PEC error"),
+            SmbusError::BlockLength => f.write_str("SMBus block length out of range"),
+        }
+    }
+}
+
+impl core::error::Error for SmbusError {}
+
+impl From<Error> for SmbusError {
+    #[inline]
+    fn from(e: Error) -> Self {
+        SmbusError::I2c(e)
+    }
+}
+
+/// Update packet error code `crc` with `data` (CRC-8, polynomial x^8 + x^2 + x + 1).
+pub const fn smbus_pec(crc: u8, data: &[u8]) -> u8 {
+    let mut crc = crc;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i];
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// SMBus host.
+pub struct Smbus<'a, const I: u8, PAD>
+where
+    PAD: I2cPads<I>,
+{
+    i2c: BlockingI2c<'a, I, PAD>,
+    pec: bool,
+}
+
+impl<'a, const I: u8, PAD> Smbus<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+{
+    /// Create a new SMBus host, appending and checking PEC if `pec`.
+    #[inline]
+    pub fn new(i2c: BlockingI2c<'a, I, PAD>, pec: bool) -> Self {
+        Self { i2c, pec }
+    }
+
+    /// Enable or disable packet error checking.
+    #[inline]
+    pub fn set_pec(&mut self, pec: bool) {
+        self.pec = pec;
+    }
+
+    /// Check that `address` acknowledges a read, reading and discarding one byte.
+    pub fn quick_read(&mut self, address: u8) -> Result<(), SmbusError> {
+        self.i2c.read(address, &mut [0])?;
+        Ok(())
+    }
+
+    /// Send byte.
+    #[inline]
+    pub fn send_byte(&mut self, address: u8, value: u8) -> Result<(), SmbusError> {
+        self.write(address, &[value], &[])
+    }
+
+    /// Receive byte.
+    pub fn receive_byte(&mut self, address: u8) -> Result<u8, SmbusError> {
+        let mut value = [0];
+        self.read(address, &[], &mut value)?;
+        Ok(value[0])
+    }
+
+    /// Write byte to `command`.
+    #[inline]
+    pub fn write_byte(&mut self, address: u8, command: u8, value: u8) -> Result<(), SmbusError> {
+        self.write(address, &[command], &[value])
+    }
+
+    /// Read byte from `command`.
+    pub fn read_byte(&mut self, address: u8, command: u8) -> Result<u8, SmbusError> {
+        let mut value = [0];
+        self.read(address, &[command], &mut value)?;
+        Ok(value[0])
+    }
+
+    /// Write word to `command`, least significant byte first.
+    #[inline]
+    pub fn write_word(&mut self, address: u8, command: u8, value: u16) -> Result<(), SmbusError> {
+        self.write(address, &[command], &value.to_le_bytes())
+    }
+
+    /// Read word from `command`, least significant byte first.
+    pub fn read_word(&mut self, address: u8, command: u8) -> Result<u16, SmbusError> {
+        let mut value = [0; 2];
+        self.read(address, &[command], &mut value)?;
+        Ok(u16::from_le_bytes(value))
+    }
+
+    /// Write word `value` to `command` and read back the word it returns.
+    pub fn process_call(
+        &mut self,
+        address: u8,
+        command: u8,
+        value: u16,
+    ) -> Result<u16, SmbusError> {
+        let [lo, hi] = value.to_le_bytes();
+        let mut result = [0; 2];
+        self.read(address, &[command, lo, hi], &mut result)?;
+        Ok(u16::from_le_bytes(result))
+    }
+
+    /// Write `data` as a block to `command`.
+    pub fn block_write(&mut self, address: u8, command: u8, data: &[u8]) -> Result<(), SmbusError> {
+        if data.is_empty() || data.len() > SMBUS_BLOCK_MAX {
+            return Err(SmbusError::BlockLength);
+        }
+        self.write(address, &[command, data.len() as u8], data)
+    }
+
+    /// Read a block from `command` into `buf`, returning its length.
+    pub fn block_read(
+        &mut self,
+        address: u8,
+        command: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, SmbusError> {
+        let mut raw = [0; SMBUS_BLOCK_MAX + 2];
+        let pec_len = self.pec as usize;
+        let len = self
+            .i2c
+            .read_counted(address as u16, &[command], pec_len, &mut raw)?
+            .ok_or(SmbusError::BlockLength)?;
+        let (block, pec) = raw[..len].split_at(len - pec_len);
+        if self.pec {
+            let crc = smbus_pec(0, &[address << 1, command, (address << 1) | 1]);
+            if smbus_pec(crc, block) != pec[0] {
+                return Err(SmbusError::Pec);
+            }
+        }
+        let data = &block[1..];
+        if data.is_empty() {
+            return Err(SmbusError::BlockLength);
+        }
+        let dst = buf.get_mut(..data.len()).ok_or(SmbusError::BlockLength)?;
+        dst.copy_from_slice(data);
+        Ok(data.len())
+    }
+
+    /// Write `data` to all targets with a general call.
+    #[inline]
+    pub fn general_call(&mut self, data: &[u8]) -> Result<(), SmbusError> {
+        self.write(SMBUS_GENERAL_CALL, data, &[])
+    }
+
+    /// Read the address of a target asserting SMBALERT#, if any.
+    ///
+    /// Targets respond in order of address, lowest first, and stop asserting
+    /// the alert once their address was read. PEC is not used.
+    pub fn alert_response(&mut self) -> Result<Option<u8>, SmbusError> {
+        let mut value = [0];
+        match self.i2c.read(SMBUS_ALERT_RESPONSE, &mut value) {
+            Ok(()) => Ok(Some(value[0] >> 1)),
+            Err(Error::AddressNack) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Free the SMBus host and return the blocking I2C.
+    #[inline]
+    pub fn free(self) -> BlockingI2c<'a, I, PAD> {
+        self.i2c
+    }
+
+    /// Write `header` and `data`, followed by PEC if enabled.
+    fn write(&mut self, address: u8, header: &[u8], data: &[u8]) -> Result<(), SmbusError> {
+        let pec = [smbus_pec(
+            smbus_pec(smbus_pec(0, &[address << 1]), header),
+            data,
+        )];
+        let pec = if self.pec { &pec[..] } else { &[] };
+        self.i2c.transaction(
+            address,
+            &mut [
+                Operation::Write(header),
+                Operation::Write(data),
+                Operation::Write(pec),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Write `header`, if any, then read `buf` after a repeated START,
+    /// followed by PEC if enabled.
+    fn read(&mut self, address: u8, header: &[u8], buf: &mut [u8]) -> Result<(), SmbusError> {
+        let mut pec = [0];
+        let pec_len = self.pec as usize;
+        let mut ops = [
+            Operation::Write(header),
+            Operation::Read(&mut *buf),
+            Operation::Read(&mut pec[..pec_len]),
+        ];
+        let skip = header.is_empty() as usize;
+        self.i2c.transaction(address, &mut ops[skip..])?;
+        if self.pec {
+            let mut crc = 0;
+            if !header.is_empty() {
+                crc = smbus_pec(crc, &[address << 1]);
+                crc = smbus_pec(crc, header);
+            }
+            crc = smbus_pec(crc, &[(address << 1) | 1]);
+            if smbus_pec(crc, buf) != pec[0] {
+                return Err(SmbusError::Pec);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::smbus_pec;
+
+    #[test]
+    fn smbus_pec_check_value() {
+        // CRC-8/SMBUS check value.
+        assert_eq!(smbus_pec(0, b"123456789"), 0xF4);
+        assert_eq!(smbus_pec(smbus_pec(0, b"1234"), b"56789"), 0xF4);
+        assert_eq!(smbus_pec(0, &[]), 0x00);
+    }
+}
@@ -0,0 +1,283 @@
+//! I2C target (slave) mode.
+
+use super::blocking::{push_write, wait_for};
+use super::config::{I2cConfig, Role};
+use super::error::Error;
+use super::instance::I2c;
+use super::pad::I2cPads;
+use super::register::{InterruptClear, RegisterBlock};
+use super::setup::{clock_register, init, set_address};
+use crate::cmu::Cmu;
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::instance::I2cInterrupt, super::non_blocking::wait_for_interrupt,
+    super::register::InterruptMask,
+};
+
+/// Bus event seen by an I2C target.
+///
+/// Events are taken from latched interrupt flags, so those of transactions
+/// not polled in between merge or lose their order; take them at least once
+/// per transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TargetEvent {
+    /// A general call was received; its data follows as [`TargetEvent::WriteData`].
+    GeneralCall,
+    /// Data written by the controller is waiting to be read.
+    WriteData,
+    /// A START or repeated START was detected, whether or not this target is addressed.
+    Start,
+    /// The controller reads from this target; queue the response.
+    ReadRequest,
+    /// A transaction addressed to this target ended.
+    Stop,
+}
+
+/// Take the next pending event.
+///
+/// Events are latched interrupt flags, not a queue: each kind is pending once
+/// at most and their order is not recorded. Received data is reported before
+/// the START, read request or STOP that follows it. A STOP latched together
+/// with a START is reported first if the target is active again, as the START
+/// then began a new transaction. Two write transactions not polled in between
+/// are reported as one. A response flushed because the controller stopped
+/// reading is discarded silently.
+fn poll_event(reg: &RegisterBlock) -> Option<TargetEvent> {
+    let raw = reg.raw_intr_stat.read();
+    let clear = |c: InterruptClear| unsafe { reg.intr_clear.write(c) };
+    if raw.is_tx_abort_pending() {
+        clear(InterruptClear::default().clear_tx_abort());
+    }
+    let stop_first = raw.is_start_detect_pending()
+        && raw.is_stop_detect_pending()
+        && reg.status.read().is_slave_active();
+    if raw.is_general_call_pending() {
+        clear(InterruptClear::default().clear_general_call());
+        Some(TargetEvent::GeneralCall)
+    } else if reg.rx_flr.read().rx_fifo_count() > 0 {
+        Some(TargetEvent::WriteData)
+    } else if stop_first {
+        // The previous transaction ended before the pending START.
+        clear(InterruptClear::default().clear_stop_detect());
+        Some(TargetEvent::Stop)
+    } else if raw.is_start_detect_pending() {
+        clear(InterruptClear::default().clear_start_detect());
+        Some(TargetEvent::Start)
+    } else if raw.is_read_request_pending() {
+        clear(InterruptClear::default().clear_read_request());
+        Some(TargetEvent::ReadRequest)
+    } else if raw.is_stop_detect_pending() {
+        clear(InterruptClear::default().clear_stop_detect());
+        Some(TargetEvent::Stop)
+    } else {
+        None
+    }
+}
+
+/// Drain received data into `buf`, returning the number of bytes read.
+fn read(reg: &RegisterBlock, buf: &mut [u8]) -> usize {
+    let mut count = 0;
+    while count < buf.len() && reg.rx_flr.read().rx_fifo_count() > 0 {
+        buf[count] = reg.data_cmd.read().data_byte();
+        count += 1;
+    }
+    count
+}
+
+fn set_ack_general_call(reg: &RegisterBlock, ack: bool) {
+    unsafe {
+        reg.ack_gen_call.modify(|v| {
+            if ack {
+                v.enable_ack_general_call()
+            } else {
+                v.disable_ack_general_call()
+            }
+        })
+    }
+}
+
+fn new_target<const I: u8>(
+    reg: &RegisterBlock,
+    address: u16,
+    config: I2cConfig,
+    cmu: &mut Cmu,
+) -> u32 {
+    let config = I2cConfig {
+        role: Role::Slave,
+        ..config
+    };
+    init::<I>(reg, &config, cmu);
+    set_address(reg, address);
+    // Report every received byte.
+    unsafe { reg.rx_tl.modify(|v| v.set_rx_threshold(0)) };
+    config.timeout
+}
+
+/// Blocking I2C target.
+pub struct I2cTarget<'a, const I: u8, PAD>
+where
+    PAD: I2cPads<I>,
+{
+    reg: &'a RegisterBlock,
+    pad: PAD,
+    timeout: u32,
+}
+
+impl<'a, const I: u8, PAD> I2cTarget<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+{
+    /// Create a new I2C target responding to `address`.
+    pub fn new(
+        reg: &'a RegisterBlock,
+        pad: PAD,
+        address: u16,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+    ) -> Self {
+        let timeout = new_target::<I>(reg, address, config, cmu);
+        Self { reg, pad, timeout }
+    }
+
+    /// Take the next pending event, if any.
+    #[inline]
+    pub fn poll_event(&mut self) -> Option<TargetEvent> {
+        poll_event(self.reg)
+    }
+
+    /// Wait for the next event.
+    pub fn wait_event(&mut self) -> TargetEvent {
+        loop {
+            if let Some(event) = poll_event(self.reg) {
+                return event;
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Acknowledge general calls if `ack`, reporting them as [`TargetEvent::GeneralCall`].
+    #[inline]
+    pub fn set_ack_general_call(&mut self, ack: bool) {
+        set_ack_general_call(self.reg, ack)
+    }
+
+    /// Read data written by the controller, returning the number of bytes read.
+    #[inline]
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        read(self.reg, buf)
+    }
+
+    /// Queue `data` as the response to a read request.
+    ///
+    /// The bus is stretched until the first byte is queued. Bytes the
+    /// controller does not read are discarded at its next read request.
+    pub fn respond(&mut self, data: &[u8]) -> Result<(), Error> {
+        for &byte in data {
+            wait_for(self.reg, self.timeout, |r| {
+                r.status.read().is_tx_fifo_not_full()
+            })?;
+            push_write(self.reg, byte, false);
+        }
+        Ok(())
+    }
+
+    /// Free the I2C target and return I2C instance, SCL and SDA pads.
+    pub fn free(self, cmu: &Cmu) -> (I2c<I>, PAD) {
+        unsafe {
+            clock_register::<I>(cmu).modify(|v| v.disable_bus_clk().enable_module_reset());
+        }
+        (I2c::__new(self.reg), self.pad)
+    }
+}
+
+/// Interrupt driven I2C target.
+#[cfg(feature = "clic_interrupts")]
+pub struct AsyncI2cTarget<'a, const I: u8, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    reg: &'a RegisterBlock,
+    pad: PAD,
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'a, const I: u8, PAD> AsyncI2cTarget<'a, I, PAD>
+where
+    PAD: I2cPads<I>,
+    I2c<I>: I2cInterrupt<I>,
+{
+    /// Create a new I2C target responding to `address`.
+    pub fn new(
+        reg: &'a RegisterBlock,
+        pad: PAD,
+        address: u16,
+        config: I2cConfig,
+        cmu: &mut Cmu,
+    ) -> Self {
+        new_target::<I>(reg, address, config, cmu);
+        Self { reg, pad }
+    }
+
+    /// Take the next pending event, if any.
+    #[inline]
+    pub fn poll_event(&mut self) -> Option<TargetEvent> {
+        poll_event(self.reg)
+    }
+
+    /// Wait for the next event.
+    pub async fn wait_event(&mut self) -> TargetEvent {
+        wait_for_interrupt(
+            self.reg,
+            <I2c<I> as I2cInterrupt<I>>::state(),
+            |v: InterruptMask| {
+                v.enable_general_call()
+                    .enable_rx_full()
+                    .enable_start_detect()
+                    .enable_read_request()
+                    .enable_stop_detect()
+                    .enable_tx_abort()
+            },
+            poll_event,
+        )
+        .await
+    }
+
+    /// Acknowledge general calls if `ack`, reporting them as [`TargetEvent::GeneralCall`].
+    #[inline]
+    pub fn set_ack_general_call(&mut self, ack: bool) {
+        set_ack_general_call(self.reg, ack)
+    }
+
+    /// Read data written by the controller, returning the number of bytes read.
+    #[inline]
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        read(self.reg, buf)
+    }
+
+    /// Queue `data` as the response to a read request.
+    ///
+    /// The bus is stretched until the first byte is queued. Bytes the
+    /// controller does not read are discarded at its next read request.
+    pub async fn respond(&mut self, data: &[u8]) {
+        for &byte in data {
+            wait_for_interrupt(
+                self.reg,
+                <I2c<I> as I2cInterrupt<I>>::state(),
+                |v: InterruptMask| v.enable_tx_empty(),
+                |r| r.status.read().is_tx_fifo_not_full().then_some(()),
+            )
+            .await;
+            push_write(self.reg, byte, false);
+        }
+    }
+
+    /// Free the I2C target and return I2C instance, SCL and SDA pads.
+    pub fn free(self, cmu: &Cmu) -> (I2c<I>, PAD) {
+        unsafe {
+            self.reg.intr_mask.modify(|v| v.disable_all());
+            clock_register::<I>(cmu).modify(|v| v.disable_bus_clk().enable_module_reset());
+        }
+        (I2c::__new(self.reg), self.pad)
+    }
+}
@@ -80,6 +80,19 @@ pub unsafe fn clear_pending(irq: u8) {
     });
 }
 
+/// Set the pending status of a specific interrupt from software.
+///
+/// # Safety
+/// The interrupt number must refer to a valid CLIC interrupt on the current SoC.
+pub unsafe fn set_pending(irq: u8) {
+    critical_section::with(|_| {
+        let clic = clic();
+        clic.interrupts[irq as usize]
+            .int_ip
+            .modify(|v| v.set_pending());
+    });
+}
+
 /// Check if a specific interrupt is currently pending.
 pub fn is_pending(irq: u8) -> bool {
     let clic = clic();
@@ -109,6 +122,9 @@ macro_rules! clic_interrupt_mod {
 
                 #[inline(always)]
                 fn clear_pending() { unsafe { $crate::interrupt::clic::clear_pending(Self::IRQ) } }
+
+                #[inline(always)]
+                fn pend() { unsafe { $crate::interrupt::clic::set_pending(Self::IRQ) } }
             }
 
             /// Drivers implement this Trait to handle interrupt logic
@@ -2,14 +2,22 @@
 
 mod blocking;
 mod config;
+mod idma;
 mod instance;
+mod memory;
+#[cfg(feature = "clic_interrupts")]
+mod non_blocking;
 mod pad;
 mod qspi_ext;
 mod register;
 
 pub use blocking::*;
 pub use config::*;
+pub use idma::{IDMA_THRESHOLD, IdmaError};
 pub use instance::Qspi;
+pub use memory::{MemCommand, MemCommandError, MemLines};
+#[cfg(feature = "clic_interrupts")]
+pub use non_blocking::*;
 pub use pad::*;
 pub use qspi_ext::QspiExt;
 pub use register::*;
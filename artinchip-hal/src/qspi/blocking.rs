@@ -9,13 +9,14 @@ use super::instance::Qspi;
 use super::pad::*;
 use super::register::*;
 use crate::cmu::Cmu;
+use core::cell::Cell;
 use embedded_hal::spi::{MODE_1, MODE_3, Operation};
 
 pub struct BlockingQspi<'a, const I: u8, PAD>
 where
     PAD: QspiPads<I>,
 {
-    reg: &'a RegisterBlock,
+    pub(super) reg: &'a RegisterBlock,
     _config: QspiConfig,
     pad: PAD,
 }
@@ -218,7 +219,7 @@ where
     }
 
     /// Reset the TX and RX FIFOs.
-    fn reset_fifos(&mut self) {
+    pub(super) fn reset_fifos(&mut self) {
         unsafe {
             self.reg
                 .fifo_control
@@ -245,39 +246,46 @@ where
     }
 
     /// Wait for the transfer to complete.
-    fn wait_transfer_done(&mut self) {
+    pub(super) fn wait_transfer_done(&mut self) {
         while self.reg.trans_config.read().start() {
             core::hint::spin_loop();
         }
     }
 
     /// Write bytes to the TX FIFO.
+    #[inline]
     fn write_bytes(&mut self, buf: &[u8]) {
-        let mut idx = 0usize;
+        self.write_stream(buf.iter().copied());
+    }
+
+    /// Write a stream of bytes to the TX FIFO, packing them into words.
+    pub(super) fn write_stream(&mut self, mut bytes: impl Iterator<Item = u8>) {
+        loop {
+            // Pack into u32 (little-endian).
+            let mut word = 0u32;
+            let mut chunk = 0;
+            while chunk < 4 {
+                let Some(byte) = bytes.next() else { break };
+                word |= (byte as u32) << (chunk * 8);
+                chunk += 1;
+            }
+            if chunk == 0 {
+                break;
+            }
 
-        while idx < buf.len() {
             // Wait for FIFO to have enough space (at least one u32).
             while self.reg.fifo_status.read().tx_fifo_count() >= Self::FIFO_DEPTH - 4 {
                 core::hint::spin_loop();
             }
 
-            // Pack into u32 (little-endian).
-            let chunk = core::cmp::min(4, buf.len() - idx);
-            let mut word = 0u32;
-            for i in 0..chunk {
-                word |= (buf[idx + i] as u32) << (i * 8);
-            }
-
             unsafe {
                 self.reg.tx_data.write(word);
             }
-
-            idx += chunk;
         }
     }
 
     /// Read bytes from the RX FIFO.
-    fn read_bytes(&self, buf: &mut [u8]) {
+    pub(super) fn read_bytes(&self, buf: &mut [u8]) {
         if buf.is_empty() {
             return;
         }
@@ -303,6 +311,67 @@ where
         }
     }
 
+    /// Exchange `len` bytes full-duplex, taking each byte to send from
+    /// `tx_byte` and passing each byte received to `rx_byte`.
+    ///
+    /// The TX FIFO is fed while the RX FIFO is drained, keeping no more bytes
+    /// in flight than the RX FIFO holds.
+    fn exchange(
+        &mut self,
+        len: usize,
+        mut tx_byte: impl FnMut(usize) -> u8,
+        mut rx_byte: impl FnMut(usize, u8),
+    ) {
+        if len == 0 {
+            return;
+        }
+        self.reset_fifos();
+        // Keep data received while transmitting.
+        unsafe {
+            self.reg
+                .trans_config
+                .modify(|v| v.disable_discard_invalid_data());
+        }
+        self.start_transfer(len, len);
+
+        let (mut tx_idx, mut rx_idx) = (0usize, 0usize);
+        while rx_idx < len {
+            let in_flight = tx_idx - rx_idx;
+            if tx_idx < len
+                && in_flight + 4 <= Self::FIFO_DEPTH as usize
+                && self.reg.fifo_status.read().tx_fifo_count() <= Self::FIFO_DEPTH - 4
+            {
+                // Pack into u32 (little-endian).
+                let chunk = core::cmp::min(4, len - tx_idx);
+                let mut word = 0u32;
+                for i in 0..chunk {
+                    word |= (tx_byte(tx_idx + i) as u32) << (i * 8);
+                }
+                unsafe {
+                    self.reg.tx_data.write(word);
+                }
+                tx_idx += chunk;
+            }
+
+            let chunk = core::cmp::min(4, len - rx_idx);
+            if rx_idx < tx_idx && self.reg.fifo_status.read().rx_fifo_count() as usize >= chunk {
+                let word = self.reg.rx_data.read();
+                for i in 0..chunk {
+                    rx_byte(rx_idx + i, ((word >> (i * 8)) & 0xFF) as u8);
+                }
+                rx_idx += chunk;
+            }
+        }
+        self.wait_transfer_done();
+
+        // Restore settings.
+        unsafe {
+            self.reg
+                .trans_config
+                .modify(|v| v.enable_discard_invalid_data());
+        }
+    }
+
     /// Calculate the best internal clock divider to achieve target frequency.
     /// - CDR1: SPI_CLK = sclk / (2^cdr1).
     /// - CDR2: SPI_CLK = sclk / (2 * (cdr2 + 1)).
@@ -431,23 +500,32 @@ where
         Ok(())
     }
 
+    /// Exchange bytes full-duplex.
+    ///
+    /// The longer buffer sets the length; `0xFF` is sent past the end of
+    /// `write` and bytes received past the end of `read` are discarded.
     fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
-        if !write.is_empty() {
-            self.write(write)?;
-        }
-        if !read.is_empty() {
-            self.read(read)?;
-        }
+        let len = read.len().max(write.len());
+        self.exchange(
+            len,
+            |idx| write.get(idx).copied().unwrap_or(0xFF),
+            |idx, byte| {
+                if let Some(dst) = read.get_mut(idx) {
+                    *dst = byte;
+                }
+            },
+        );
         Ok(())
     }
 
     fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-        let mut temp = [0u8; 256];
-        for chunk in words.chunks_mut(256) {
-            let len = chunk.len();
-            temp[..len].copy_from_slice(chunk);
-            self.transfer(chunk, &temp[..len])?;
-        }
+        // Each byte is sent before the byte received in its place.
+        let words = Cell::from_mut(words).as_slice_of_cells();
+        self.exchange(
+            words.len(),
+            |idx| words[idx].get(),
+            |idx, byte| words[idx].set(byte),
+        );
         Ok(())
     }
 
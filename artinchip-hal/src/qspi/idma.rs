@@ -0,0 +1,357 @@
+//! QSPI transfers through the controller's internal DMA (IDMA).
+//!
+//! Buffers are written back from or invalidated in the data cache by these
+//! functions. Transfers shorter than [`IDMA_THRESHOLD`] or from buffers not
+//! starting and ending on a cache line boundary go through the FIFO instead,
+//! so that invalidating a buffer cannot discard neighbouring data.
+
+use embedded_hal::spi::SpiBus as _;
+
+use super::blocking::BlockingQspi;
+use super::memory::{MemCommand, MemCommandError};
+use super::pad::QspiPads;
+use super::register::{IdmaRxBurstLen, IdmaTxBurstLen, RegisterBlock};
+use crate::dma::cache;
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::instance::{Qspi, QspiInterrupt},
+    super::non_blocking::{AsyncQspi, AsyncState},
+    super::register::TransMiscControl,
+    core::future::poll_fn,
+    core::task::Poll,
+};
+
+/// Transfers shorter than this use the FIFO instead of IDMA.
+pub const IDMA_THRESHOLD: usize = 256;
+/// Longest transfer, limited by the byte counters.
+const IDMA_MAX_LEN: usize = 0xFF_FFFF;
+/// Polls per byte to wait for a blocking IDMA transfer, enough for a byte at
+/// the lowest bus clock.
+const IDMA_SPINS_PER_BYTE: usize = 100_000;
+
+/// IDMA transfer error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IdmaError {
+    /// IDMA bus error.
+    Bus,
+    /// The memory command is not supported by the controller.
+    Command(MemCommandError),
+    /// A blocking transfer did not end in time; the controller was reset.
+    Timeout,
+}
+
+impl core::fmt::Display for IdmaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IdmaError::Bus => f.write_str("QSPI IDMA error"),
+            IdmaError::Command(e) => write!(f, "invalid memory command: {e}"),
+            IdmaError::Timeout => f.write_str("QSPI IDMA timeout"),
+        }
+    }
+}
+
+impl core::error::Error for IdmaError {}
+
+impl From<MemCommandError> for IdmaError {
+    #[inline]
+    fn from(e: MemCommandError) -> Self {
+        IdmaError::Command(e)
+    }
+}
+
+/// Direction of an IDMA transfer.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Memory to TX FIFO.
+    Tx,
+    /// RX FIFO to memory.
+    Rx,
+}
+
+#[inline]
+fn use_idma(buf: &[u8]) -> bool {
+    buf.len() >= IDMA_THRESHOLD && cache::is_line_aligned(buf.as_ptr() as usize, buf.len())
+}
+
+/// Program the IDMA of `dir` for `len` bytes at `addr` and enable it.
+fn setup_idma(reg: &RegisterBlock, dir: Direction, addr: u32, len: usize) {
+    unsafe {
+        reg.int_status.modify(|v| v.clear_all_int());
+        reg.idma_burst_cfg.modify(|v| {
+            v.enable_auto_len()
+                .set_tx_burst_len(IdmaTxBurstLen::Burst16)
+                .set_rx_burst_len(IdmaRxBurstLen::Burst16)
+        });
+        match dir {
+            Direction::Tx => {
+                reg.idma_tx_addr.write(addr);
+                reg.idma_tx_len.modify(|v| v.set_idma_tx_len(len as u32));
+                reg.config.modify(|v| v.enable_tx_idma());
+            }
+            Direction::Rx => {
+                reg.idma_rx_addr.write(addr);
+                reg.idma_rx_len.modify(|v| v.set_idma_rx_len(len as u32));
+                reg.config.modify(|v| v.enable_rx_idma());
+            }
+        }
+    }
+}
+
+/// Check whether the IDMA of `dir` finished or failed.
+fn idma_status(reg: &RegisterBlock, dir: Direction) -> Option<Result<(), IdmaError>> {
+    let status = reg.int_status.read();
+    let done = match dir {
+        Direction::Tx => status.is_tx_dma_done_int_pending(),
+        Direction::Rx => status.is_rx_dma_done_int_pending(),
+    };
+    if status.is_idma_error_int_pending() {
+        Some(Err(IdmaError::Bus))
+    } else if done {
+        Some(Ok(()))
+    } else {
+        None
+    }
+}
+
+/// Disable the IDMA, waiting for the transfer to end, or resetting the
+/// controller state after an error.
+fn finish_idma(reg: &RegisterBlock, result: Result<(), IdmaError>) {
+    unsafe {
+        reg.config.modify(|v| v.disable_tx_idma().disable_rx_idma());
+        match result {
+            Ok(()) => {
+                while reg.trans_config.read().start() {
+                    core::hint::spin_loop();
+                }
+            }
+            Err(_) => reset_controller(reg),
+        }
+        reg.int_status.modify(|v| v.clear_all_int());
+    }
+}
+
+/// Reset the controller state, ending any transfer in progress.
+fn reset_controller(reg: &RegisterBlock) {
+    unsafe {
+        reg.config.modify(|v| v.set_ctrl_rst(true));
+        riscv::asm::delay(500);
+        reg.config.modify(|v| v.set_ctrl_rst(false));
+    }
+}
+
+/// Start a transfer of `total` bytes sending the first `tx` of them, on the
+/// lines of the work mode.
+fn start_transfer(reg: &RegisterBlock, total: usize, tx: usize) {
+    assert!(total <= IDMA_MAX_LEN, "Transfer too long for IDMA");
+    unsafe {
+        reg.total_bytes_cnt
+            .modify(|v| v.set_total_bytes(total as u32));
+        reg.trans_write_cnt.modify(|v| v.set_tx_cnt(tx as u32));
+        reg.trans_misc_control
+            .modify(|v| v.set_single_tx_count(tx as u32));
+        reg.trans_config.modify(|v| v.set_start(true));
+    }
+}
+
+impl<'a, const I: u8, PAD> BlockingQspi<'a, I, PAD>
+where
+    PAD: QspiPads<I>,
+{
+    /// Write `buf` through IDMA, blocking until it is sent.
+    ///
+    /// A stalled transfer resets the controller and fails with
+    /// [`IdmaError::Timeout`].
+    pub fn write_idma(&mut self, buf: &[u8]) -> Result<(), IdmaError> {
+        if !use_idma(buf) {
+            let Ok(()) = self.write(buf);
+            return Ok(());
+        }
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        self.reset_fifos();
+        setup_idma(self.reg, Direction::Tx, buf.as_ptr() as u32, buf.len());
+        start_transfer(self.reg, buf.len(), buf.len());
+        self.wait_idma(Direction::Tx, buf.len())
+    }
+
+    /// Fill `buf` through IDMA, blocking until it is full.
+    ///
+    /// A stalled transfer resets the controller and fails with
+    /// [`IdmaError::Timeout`].
+    pub fn read_idma(&mut self, buf: &mut [u8]) -> Result<(), IdmaError> {
+        if !use_idma(buf) {
+            let Ok(()) = self.read(buf);
+            return Ok(());
+        }
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        self.reset_fifos();
+        setup_idma(self.reg, Direction::Rx, buf.as_mut_ptr() as u32, buf.len());
+        start_transfer(self.reg, buf.len(), 0);
+        let result = self.wait_idma(Direction::Rx, buf.len());
+        cache::invalidate(buf.as_ptr() as usize, buf.len());
+        result
+    }
+
+    /// Run `cmd` and read its data phase into `buf` through IDMA, blocking
+    /// until it is full.
+    ///
+    /// The command itself is sent through the FIFO. Nothing is sent if `cmd`
+    /// is not supported by the controller. A stalled transfer resets the
+    /// controller and fails with [`IdmaError::Timeout`].
+    pub fn mem_read_idma(&mut self, cmd: &MemCommand, buf: &mut [u8]) -> Result<(), IdmaError> {
+        if !use_idma(buf) {
+            return self.mem_read(cmd, buf).map_err(IdmaError::from);
+        }
+        let header = cmd.header()?;
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        self.reset_fifos();
+        setup_idma(self.reg, Direction::Rx, buf.as_mut_ptr() as u32, buf.len());
+        let tmc = self.begin_mem_transfer(cmd, &header, &[], buf.len());
+        let result = self.wait_idma(Direction::Rx, buf.len());
+        unsafe { self.reg.trans_misc_control.write(tmc) };
+        cache::invalidate(buf.as_ptr() as usize, buf.len());
+        result
+    }
+
+    /// Wait for the IDMA of `dir` to move `len` bytes, resetting the
+    /// controller if it does not finish in time.
+    fn wait_idma(&mut self, dir: Direction, len: usize) -> Result<(), IdmaError> {
+        let limit = len.saturating_mul(IDMA_SPINS_PER_BYTE);
+        let mut spins = 0;
+        let result = loop {
+            if let Some(result) = idma_status(self.reg, dir) {
+                break result;
+            }
+            spins += 1;
+            if spins >= limit {
+                break Err(IdmaError::Timeout);
+            }
+            core::hint::spin_loop();
+        };
+        finish_idma(self.reg, result);
+        result
+    }
+}
+
+/// Stops the transfer if its future is dropped, so the IDMA no longer
+/// accesses a buffer that may be reused, and restores the line
+/// configuration.
+///
+/// A cancelled transfer may never end by itself, e.g. a receive stalled on a
+/// full FIFO, so the controller is reset instead of waited for.
+#[cfg(feature = "clic_interrupts")]
+struct FinishOnDrop<'r> {
+    reg: &'r RegisterBlock,
+    tmc: Option<TransMiscControl>,
+    armed: bool,
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl Drop for FinishOnDrop<'_> {
+    fn drop(&mut self) {
+        unsafe { self.reg.int_control.modify(|v| v.disable_all_int()) };
+        if self.armed {
+            finish_idma(self.reg, Err(IdmaError::Bus));
+        }
+        if let Some(tmc) = self.tmc {
+            unsafe { self.reg.trans_misc_control.write(tmc) };
+        }
+    }
+}
+
+/// Wait until the IDMA of `dir` finished or failed.
+#[cfg(feature = "clic_interrupts")]
+async fn async_wait(
+    reg: &RegisterBlock,
+    state: &AsyncState,
+    dir: Direction,
+    tmc: Option<TransMiscControl>,
+) -> Result<(), IdmaError> {
+    let mut guard = FinishOnDrop {
+        reg,
+        tmc,
+        armed: true,
+    };
+    let result = poll_fn(|cx| {
+        state.waker.register(cx.waker());
+        if let Some(result) = idma_status(reg, dir) {
+            return Poll::Ready(result);
+        }
+        unsafe {
+            reg.int_control.modify(|v| {
+                let v = v.enable_idma_error_int();
+                match dir {
+                    Direction::Tx => v.enable_tx_dma_done_int(),
+                    Direction::Rx => v.enable_rx_dma_done_int(),
+                }
+            })
+        };
+        Poll::Pending
+    })
+    .await;
+    guard.armed = false;
+    finish_idma(reg, result);
+    result
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'a, const I: u8, PAD> AsyncQspi<'a, I, PAD>
+where
+    PAD: QspiPads<I>,
+    Qspi<I>: QspiInterrupt<I>,
+{
+    /// Write `buf` through IDMA.
+    ///
+    /// Dropping the future aborts the transfer by resetting the controller.
+    pub async fn write_idma(&mut self, buf: &[u8]) -> Result<(), IdmaError> {
+        if !use_idma(buf) {
+            return self.qspi.write_idma(buf);
+        }
+        let reg = self.qspi.reg;
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        self.qspi.reset_fifos();
+        setup_idma(reg, Direction::Tx, buf.as_ptr() as u32, buf.len());
+        start_transfer(reg, buf.len(), buf.len());
+        async_wait(reg, Self::state(), Direction::Tx, None).await
+    }
+
+    /// Fill `buf` through IDMA.
+    ///
+    /// Dropping the future aborts the transfer by resetting the controller.
+    pub async fn read_idma(&mut self, buf: &mut [u8]) -> Result<(), IdmaError> {
+        if !use_idma(buf) {
+            return self.qspi.read_idma(buf);
+        }
+        let reg = self.qspi.reg;
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        self.qspi.reset_fifos();
+        setup_idma(reg, Direction::Rx, buf.as_mut_ptr() as u32, buf.len());
+        start_transfer(reg, buf.len(), 0);
+        let result = async_wait(reg, Self::state(), Direction::Rx, None).await;
+        cache::invalidate(buf.as_ptr() as usize, buf.len());
+        result
+    }
+
+    /// Run `cmd` and read its data phase into `buf` through IDMA.
+    ///
+    /// The command itself is sent through the FIFO; nothing is sent if `cmd`
+    /// is not supported by the controller. Dropping the future aborts the
+    /// transfer by resetting the controller.
+    pub async fn mem_read_idma(
+        &mut self,
+        cmd: &MemCommand,
+        buf: &mut [u8],
+    ) -> Result<(), IdmaError> {
+        if !use_idma(buf) {
+            return self.qspi.mem_read_idma(cmd, buf);
+        }
+        let header = cmd.header()?;
+        let reg = self.qspi.reg;
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        self.qspi.reset_fifos();
+        setup_idma(reg, Direction::Rx, buf.as_mut_ptr() as u32, buf.len());
+        let tmc = self.qspi.begin_mem_transfer(cmd, &header, &[], buf.len());
+        let result = async_wait(reg, Self::state(), Direction::Rx, Some(tmc)).await;
+        cache::invalidate(buf.as_ptr() as usize, buf.len());
+        result
+    }
+}
@@ -7,6 +7,45 @@ use super::qspi_ext::QspiExt;
 use super::register::RegisterBlock;
 use crate::cmu::Cmu;
 use core::marker::PhantomData;
+#[cfg(feature = "clic_interrupts")]
+use {super::non_blocking::*, crate::interrupt::clic::typelevel};
+
+/// Trait to map const generic I to its interrupt type (used for compile-time safety).
+#[cfg(feature = "clic_interrupts")]
+pub trait QspiInterrupt<const I: u8> {
+    type Interrupt: typelevel::Interrupt;
+    /// Async state of this instance.
+    fn state() -> &'static AsyncState;
+}
+
+// Macro to quickly map instance numbers to interrupt types
+#[cfg(feature = "clic_interrupts")]
+macro_rules! impl_qspi_interrupts {
+    ( $( ($inst:literal, $irq_type:ident) ),* $(,)? ) => {
+        $(
+            impl QspiInterrupt<$inst> for Qspi<$inst> {
+                type Interrupt = crate::interrupt::clic::typelevel::$irq_type;
+                #[inline]
+                fn state() -> &'static AsyncState {
+                    static STATE: AsyncState = AsyncState::new();
+                    &STATE
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl_qspi_interrupts! {
+    (0, QSPI0),
+    (1, QSPI1),
+}
+#[cfg(feature = "clic_interrupts")]
+#[cfg(not(feature = "d12x"))]
+impl_qspi_interrupts! {
+    (2, QSPI2),
+    (3, QSPI3),
+}
 
 /// QSPI with statically known instance number.
 pub struct Qspi<const I: u8> {
@@ -27,6 +66,15 @@ impl<const I: u8> Qspi<I> {
     pub const fn register_block(&self) -> &'static RegisterBlock {
         unsafe { &*self.reg }
     }
+
+    /// Get register block for a specific index (used by Interrupt Handler).
+    #[cfg(feature = "clic_interrupts")]
+    #[inline(always)]
+    pub(crate) unsafe fn regs_at_index() -> &'static RegisterBlock {
+        let base_addr = 0x10400000 + (I as usize) * 0x10000;
+
+        unsafe { &*(base_addr as *const RegisterBlock) }
+    }
 }
 
 impl<const I: u8> QspiExt<'static, I> for Qspi<I> {
@@ -42,4 +90,21 @@ impl<const I: u8> QspiExt<'static, I> for Qspi<I> {
     {
         BlockingQspi::new(self.register_block(), pad, config, cmu)
     }
+    #[cfg(feature = "clic_interrupts")]
+    #[inline]
+    fn new_async<PAD, IRQS>(
+        self,
+        pad: PAD,
+        config: QspiConfig,
+        cmu: &mut Cmu,
+        _irqs: IRQS,
+    ) -> AsyncQspi<'static, I, PAD>
+    where
+        PAD: QspiPads<I>,
+        Qspi<I>: QspiInterrupt<I>,
+        AsyncQspiHandler<I>: typelevel::Handler<<Qspi<I> as QspiInterrupt<I>>::Interrupt>,
+        IRQS: typelevel::Binding<<Qspi<I> as QspiInterrupt<I>>::Interrupt, AsyncQspiHandler<I>>,
+    {
+        AsyncQspi::new(self.register_block(), pad, config, cmu)
+    }
 }
@@ -0,0 +1,378 @@
+//! Serial memory commands on dual and quad lines.
+//!
+//! A command has an opcode, optional address, dummy cycles and a data
+//! phase, each sent on one, two or four lines, e.g. 1-1-4 for a quad output
+//! fast read. The controller sends the leading part of a command on a single
+//! line and the rest on the data lines; dummy cycles are generated by the
+//! controller in bytes on the lines of the address phase.
+
+use super::blocking::BlockingQspi;
+use super::pad::QspiPads;
+use super::register::{DummyWidth, TransMiscControl};
+
+/// Longest opcode and address bytes of a command.
+const MEM_HEADER_MAX: usize = 5;
+/// Most dummy bytes the controller generates.
+const MEM_DUMMY_MAX: usize = 15;
+
+/// Number of lines a command phase is transferred on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum MemLines {
+    /// One line (MOSI out, MISO in).
+    Single = 1,
+    /// Two lines.
+    Dual = 2,
+    /// Four lines.
+    Quad = 4,
+}
+
+/// Invalid serial memory command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemCommandError {
+    /// The opcode is sent on dual lines, which the controller does not support.
+    DualOpcode,
+    /// A phase uses fewer lines than the one before it, or the data phase
+    /// does not use the lines of a multi-line opcode or address.
+    Lines,
+    /// Dummy cycles on dual lines, which the controller does not generate.
+    DualDummy,
+    /// Dummy cycles do not fill 0 to 15 whole bytes on the address lines.
+    DummyCycles,
+    /// Address length out of range (expected 1..=4).
+    AddressLength,
+}
+
+impl core::fmt::Display for MemCommandError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MemCommandError::DualOpcode => f.write_str("dual line opcodes are not supported"),
+            MemCommandError::Lines => f.write_str("unsupported command line sequence"),
+            MemCommandError::DualDummy => {
+                f.write_str("dummy cycles on dual lines are not supported")
+            }
+            MemCommandError::DummyCycles => {
+                f.write_str("dummy cycles do not fill 0 to 15 whole bytes")
+            }
+            MemCommandError::AddressLength => f.write_str("address length out of range"),
+        }
+    }
+}
+
+impl core::error::Error for MemCommandError {}
+
+/// Serial memory command.
+///
+/// Built with [`MemCommand::new`] and the `with_*` methods.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MemCommand {
+    /// Command opcode.
+    opcode: u8,
+    /// Lines of the opcode; [`MemLines::Quad`] selects QPI (4-4-4).
+    opcode_lines: MemLines,
+    /// Address, sent most significant byte first.
+    address: Option<u32>,
+    /// Address length in bytes, 1 to 4.
+    address_bytes: u8,
+    /// Lines of the address and dummy cycles.
+    address_lines: MemLines,
+    /// Dummy cycles between address and data.
+    dummy_cycles: u8,
+    /// Lines of the data phase.
+    data_lines: MemLines,
+}
+
+/// Opcode and address bytes of a command with its dummy phase.
+pub(super) struct MemHeader {
+    buf: [u8; MEM_HEADER_MAX],
+    /// Opcode and address bytes in `buf`.
+    len: usize,
+    /// Leading bytes sent on a single line.
+    single: usize,
+    /// Dummy bytes generated by the controller.
+    dummy: u8,
+    /// Lines of the dummy bytes.
+    dummy_width: DummyWidth,
+}
+
+impl MemCommand {
+    /// Create a single line command with `opcode`.
+    #[inline]
+    pub const fn new(opcode: u8) -> Self {
+        Self {
+            opcode,
+            opcode_lines: MemLines::Single,
+            address: None,
+            address_bytes: 3,
+            address_lines: MemLines::Single,
+            dummy_cycles: 0,
+            data_lines: MemLines::Single,
+        }
+    }
+    /// Add `address` of `bytes` length sent on `lines`.
+    ///
+    /// Running the command fails with [`MemCommandError::AddressLength`]
+    /// unless `bytes` is 1 to 4.
+    #[inline]
+    pub const fn with_address(self, address: u32, bytes: u8, lines: MemLines) -> Self {
+        Self {
+            address: Some(address),
+            address_bytes: bytes,
+            address_lines: lines,
+            ..self
+        }
+    }
+    /// Set dummy cycles between address and data.
+    #[inline]
+    pub const fn with_dummy_cycles(self, cycles: u8) -> Self {
+        Self {
+            dummy_cycles: cycles,
+            ..self
+        }
+    }
+    /// Set lines of the data phase.
+    #[inline]
+    pub const fn with_data_lines(self, lines: MemLines) -> Self {
+        Self {
+            data_lines: lines,
+            ..self
+        }
+    }
+    /// Send every phase on four lines (QPI).
+    #[inline]
+    pub const fn qpi(self) -> Self {
+        Self {
+            opcode_lines: MemLines::Quad,
+            address_lines: MemLines::Quad,
+            data_lines: MemLines::Quad,
+            ..self
+        }
+    }
+
+    /// Build the opcode and address bytes and the dummy phase.
+    pub(super) fn header(&self) -> Result<MemHeader, MemCommandError> {
+        // Phases after the first multi-line one share its lines.
+        let address_lines = if self.address.is_some() {
+            self.address_lines
+        } else {
+            self.opcode_lines
+        };
+        if self.opcode_lines == MemLines::Dual {
+            return Err(MemCommandError::DualOpcode);
+        }
+        // Command lines must not decrease and a multi-line phase sets the data lines.
+        let lines_valid = (self.opcode_lines == MemLines::Single
+            || address_lines == self.opcode_lines)
+            && (address_lines == MemLines::Single || self.data_lines == address_lines);
+        if !lines_valid {
+            return Err(MemCommandError::Lines);
+        }
+
+        // The controller counts dummy cycles in bytes sent on one or four lines.
+        let dummy_bits = self.dummy_cycles as usize * address_lines as usize;
+        let dummy_width = match address_lines {
+            MemLines::Single => DummyWidth::OneBit,
+            MemLines::Quad => DummyWidth::FourBit,
+            MemLines::Dual if self.dummy_cycles == 0 => DummyWidth::OneBit,
+            MemLines::Dual => return Err(MemCommandError::DualDummy),
+        };
+        if !dummy_bits.is_multiple_of(8) || dummy_bits / 8 > MEM_DUMMY_MAX {
+            return Err(MemCommandError::DummyCycles);
+        }
+
+        let mut buf = [0; MEM_HEADER_MAX];
+        let mut len = 0;
+        buf[len] = self.opcode;
+        len += 1;
+        if let Some(address) = self.address {
+            if !matches!(self.address_bytes, 1..=4) {
+                return Err(MemCommandError::AddressLength);
+            }
+            let bytes = address.to_be_bytes();
+            let addr = &bytes[4 - self.address_bytes as usize..];
+            buf[len..len + addr.len()].copy_from_slice(addr);
+            len += addr.len();
+        }
+
+        let single = if self.opcode_lines != MemLines::Single {
+            0
+        } else if address_lines != MemLines::Single {
+            1
+        } else {
+            len
+        };
+        Ok(MemHeader {
+            buf,
+            len,
+            single,
+            dummy: (dummy_bits / 8) as u8,
+            dummy_width,
+        })
+    }
+}
+
+impl<'a, const I: u8, PAD> BlockingQspi<'a, I, PAD>
+where
+    PAD: QspiPads<I>,
+{
+    /// Run `cmd` and read its data phase into `buf`.
+    ///
+    /// Multi-line phases need the IO2 and IO3 pads for quad lines. Nothing
+    /// is sent if `cmd` is not supported by the controller.
+    #[inline]
+    pub fn mem_read(&mut self, cmd: &MemCommand, buf: &mut [u8]) -> Result<(), MemCommandError> {
+        self.mem_transfer(cmd, &[], buf)
+    }
+
+    /// Run `cmd` with `data` as its data phase.
+    ///
+    /// Nothing is sent if `cmd` is not supported by the controller.
+    #[inline]
+    pub fn mem_write(&mut self, cmd: &MemCommand, data: &[u8]) -> Result<(), MemCommandError> {
+        self.mem_transfer(cmd, data, &mut [])
+    }
+
+    fn mem_transfer(
+        &mut self,
+        cmd: &MemCommand,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), MemCommandError> {
+        let header = cmd.header()?;
+        self.reset_fifos();
+        let tmc = self.begin_mem_transfer(cmd, &header, write, read.len());
+        self.read_bytes(read);
+        self.wait_transfer_done();
+
+        // Restore the line configuration of the work mode.
+        unsafe { self.reg.trans_misc_control.write(tmc) };
+        Ok(())
+    }
+
+    /// Start `cmd` with its `header`, `write` as data to send and `read_len`
+    /// bytes to receive, returning the line configuration to restore
+    /// afterwards.
+    pub(super) fn begin_mem_transfer(
+        &mut self,
+        cmd: &MemCommand,
+        header: &MemHeader,
+        write: &[u8],
+        read_len: usize,
+    ) -> TransMiscControl {
+        let tx_len = header.len + write.len();
+
+        let tmc = self.reg.trans_misc_control.read();
+        unsafe {
+            self.reg.burst_set.modify(|v| {
+                v.set_dummy_byte_cnt(header.dummy)
+                    .set_dummy_width(header.dummy_width)
+            });
+            self.reg
+                .total_bytes_cnt
+                .modify(|v| v.set_total_bytes((tx_len + header.dummy as usize + read_len) as u32));
+            self.reg
+                .trans_write_cnt
+                .modify(|v| v.set_tx_cnt(tx_len as u32));
+            self.reg.trans_misc_control.write({
+                let v = tmc
+                    .disable_dual()
+                    .disable_quad()
+                    .disable_qaddr()
+                    .disable_qpi()
+                    .set_dummy_count(header.dummy)
+                    .set_single_tx_count(header.single as u32);
+                let v = match cmd.data_lines {
+                    MemLines::Single => v,
+                    MemLines::Dual => v.enable_dual(),
+                    MemLines::Quad => v.enable_quad(),
+                };
+                match (cmd.opcode_lines, header.single) {
+                    (MemLines::Quad, _) => v.enable_qpi(),
+                    (_, 1) if cmd.address_lines == MemLines::Quad => v.enable_qaddr(),
+                    _ => v,
+                }
+            });
+            self.reg.trans_config.modify(|v| v.set_start(true));
+        }
+
+        self.write_stream(header.buf[..header.len].iter().chain(write).copied());
+        tmc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DummyWidth, MemCommand, MemCommandError, MemLines};
+
+    #[test]
+    fn header_quad_output_read() {
+        // 1-1-4 fast read with 8 dummy cycles.
+        let cmd = MemCommand::new(0x6B)
+            .with_address(0x123456, 3, MemLines::Single)
+            .with_dummy_cycles(8)
+            .with_data_lines(MemLines::Quad);
+        let header = cmd.header().unwrap();
+        assert_eq!(&header.buf[..header.len], &[0x6B, 0x12, 0x34, 0x56]);
+        assert_eq!(header.single, 4);
+        assert_eq!(header.dummy, 1);
+        assert_eq!(header.dummy_width, DummyWidth::OneBit);
+    }
+
+    #[test]
+    fn header_quad_io_read() {
+        // 1-4-4 fast read with mode and dummy cycles.
+        let cmd = MemCommand::new(0xEB)
+            .with_address(0x123456, 3, MemLines::Quad)
+            .with_dummy_cycles(6)
+            .with_data_lines(MemLines::Quad);
+        let header = cmd.header().unwrap();
+        assert_eq!(&header.buf[..header.len], &[0xEB, 0x12, 0x34, 0x56]);
+        assert_eq!(header.single, 1);
+        assert_eq!(header.dummy, 3);
+        assert_eq!(header.dummy_width, DummyWidth::FourBit);
+    }
+
+    #[test]
+    fn header_qpi_read() {
+        // 4-4-4 fast read.
+        let cmd = MemCommand::new(0x0B)
+            .with_address(0x00_0100, 4, MemLines::Quad)
+            .with_dummy_cycles(2)
+            .qpi();
+        let header = cmd.header().unwrap();
+        assert_eq!(&header.buf[..header.len], &[0x0B, 0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(header.single, 0);
+        assert_eq!(header.dummy, 1);
+        assert_eq!(header.dummy_width, DummyWidth::FourBit);
+    }
+
+    #[test]
+    fn header_invalid() {
+        let dual_io = MemCommand::new(0xBB)
+            .with_address(0, 3, MemLines::Dual)
+            .with_data_lines(MemLines::Dual);
+        assert!(dual_io.header().is_ok());
+        assert_eq!(
+            dual_io.with_dummy_cycles(4).header().err(),
+            Some(MemCommandError::DualDummy)
+        );
+        assert_eq!(
+            MemCommand::new(0x0B).with_dummy_cycles(4).header().err(),
+            Some(MemCommandError::DummyCycles)
+        );
+        assert_eq!(
+            MemCommand::new(0x03)
+                .with_address(0, 5, MemLines::Single)
+                .header()
+                .err(),
+            Some(MemCommandError::AddressLength)
+        );
+        assert_eq!(
+            MemCommand::new(0xEB)
+                .with_address(0, 3, MemLines::Quad)
+                .header()
+                .err(),
+            Some(MemCommandError::Lines)
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Interrupt driven QSPI interface.
+
+use embassy_sync::waitqueue::AtomicWaker;
+
+use super::blocking::BlockingQspi;
+use super::config::QspiConfig;
+use super::instance::{Qspi, QspiInterrupt};
+use super::memory::{MemCommand, MemCommandError};
+use super::pad::QspiPads;
+use super::register::RegisterBlock;
+use crate::cmu::Cmu;
+use crate::interrupt::clic::typelevel::{self, Interrupt as _};
+
+/// Async state of one QSPI instance.
+pub struct AsyncState {
+    pub waker: AtomicWaker,
+}
+
+impl AsyncState {
+    pub const fn new() -> Self {
+        Self {
+            waker: AtomicWaker::new(),
+        }
+    }
+}
+
+impl Default for AsyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// QSPI interrupt handler.
+///
+/// Disables all interrupts and wakes the waiting task, which checks the
+/// status and enables the interrupts it waits for again.
+pub struct AsyncQspiHandler<const I: u8>;
+
+impl<const I: u8> typelevel::Handler<<Qspi<I> as QspiInterrupt<I>>::Interrupt>
+    for AsyncQspiHandler<I>
+where
+    Qspi<I>: QspiInterrupt<I>,
+{
+    unsafe fn on_interrupt() {
+        let reg = unsafe { Qspi::<I>::regs_at_index() };
+        unsafe { reg.int_control.modify(|v| v.disable_all_int()) };
+        <Qspi<I> as QspiInterrupt<I>>::state().waker.wake();
+        <Qspi<I> as QspiInterrupt<I>>::Interrupt::clear_pending();
+    }
+}
+
+/// Interrupt driven QSPI interface.
+///
+/// Bulk transfers go through the internal DMA and complete on its interrupt;
+/// short transfers and memory writes use the FIFO.
+pub struct AsyncQspi<'a, const I: u8, PAD>
+where
+    PAD: QspiPads<I>,
+    Qspi<I>: QspiInterrupt<I>,
+{
+    pub(super) qspi: BlockingQspi<'a, I, PAD>,
+}
+
+impl<'a, const I: u8, PAD> AsyncQspi<'a, I, PAD>
+where
+    PAD: QspiPads<I>,
+    Qspi<I>: QspiInterrupt<I>,
+{
+    /// Create a new interrupt driven QSPI interface.
+    pub fn new(reg: &'a RegisterBlock, pad: PAD, config: QspiConfig, cmu: &mut Cmu) -> Self {
+        Self {
+            qspi: BlockingQspi::new(reg, pad, config, cmu),
+        }
+    }
+
+    #[inline]
+    pub(super) fn state() -> &'static AsyncState {
+        <Qspi<I> as QspiInterrupt<I>>::state()
+    }
+
+    /// Run `cmd` with `data` as its data phase through the FIFO.
+    ///
+    /// Nothing is sent if `cmd` is not supported by the controller.
+    #[inline]
+    pub fn mem_write(&mut self, cmd: &MemCommand, data: &[u8]) -> Result<(), MemCommandError> {
+        self.qspi.mem_write(cmd, data)
+    }
+
+    /// Free the async QSPI and return QSPI instance and all pads.
+    #[inline]
+    pub fn free(self, cmu: &Cmu) -> (Qspi<I>, PAD) {
+        self.qspi.free(cmu)
+    }
+}
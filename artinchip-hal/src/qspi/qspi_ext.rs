@@ -4,6 +4,8 @@ use super::blocking::BlockingQspi;
 use super::config::QspiConfig;
 use super::pad::*;
 use crate::cmu::Cmu;
+#[cfg(feature = "clic_interrupts")]
+use {super::instance::*, super::non_blocking::*, crate::interrupt::clic::typelevel};
 
 pub trait QspiExt<'a, const I: u8> {
     /// Creates a blocking QSPI interface with the specified pads.
@@ -15,4 +17,18 @@ pub trait QspiExt<'a, const I: u8> {
     ) -> BlockingQspi<'a, I, PAD>
     where
         PAD: QspiPads<I>;
+    /// Creates an interrupt driven QSPI interface with the specified pads.
+    #[cfg(feature = "clic_interrupts")]
+    fn new_async<PAD, IRQS>(
+        self,
+        pad: PAD,
+        config: QspiConfig,
+        cmu: &mut Cmu,
+        _irqs: IRQS,
+    ) -> AsyncQspi<'a, I, PAD>
+    where
+        PAD: QspiPads<I>,
+        Qspi<I>: QspiInterrupt<I>,
+        AsyncQspiHandler<I>: typelevel::Handler<<Qspi<I> as QspiInterrupt<I>>::Interrupt>,
+        IRQS: typelevel::Binding<<Qspi<I> as QspiInterrupt<I>>::Interrupt, AsyncQspiHandler<I>>;
 }
@@ -51,6 +51,15 @@ impl<'a> RealTime<'a> {
         Self { reg }
     }
 
+    /// Access an RTC that is already running without initializing it again.
+    ///
+    /// Time, alarm and analog settings are kept; the RTC bus clock must be
+    /// enabled.
+    #[inline]
+    pub const fn attach(reg: &'a RegisterBlock) -> Self {
+        Self { reg }
+    }
+
     /// Set time.
     pub fn set_time(&mut self, time: u32) {
         unsafe {
@@ -119,6 +128,70 @@ impl<'a> RealTime<'a> {
         alarm
     }
 
+    /// Check if the alarm interrupt is pending.
+    pub fn is_alarm_pending(&self) -> bool {
+        self.reg.aon_int_status.read().alarm_irq_status()
+    }
+
+    /// Clear the pending alarm interrupt.
+    pub fn clear_alarm_pending(&mut self) {
+        unsafe {
+            // Enable write.
+            self.reg
+                .write_key
+                .modify(|v| v.set_write_key(Self::WRITE_EN_KEY));
+
+            self.reg
+                .aon_int_status
+                .modify(|v| v.clear_alarm_irq_status());
+
+            // Disable write.
+            self.reg.write_key.modify(|v| v.set_write_key(0));
+        }
+    }
+
+    /// Read system backup register `index`.
+    ///
+    /// Backup registers keep their value across warm resets.
+    pub fn backup(&self, index: usize) -> u8 {
+        self.reg.sys_backup[index].read().sys_bak()
+    }
+
+    /// Write system backup register `index`.
+    pub fn set_backup(&mut self, index: usize, value: u8) {
+        unsafe {
+            // Enable write.
+            self.reg
+                .write_key
+                .modify(|v| v.set_write_key(Self::WRITE_EN_KEY));
+
+            self.reg.sys_backup[index].modify(|v| v.set_sys_bak(value));
+
+            // Disable write.
+            self.reg.write_key.modify(|v| v.set_write_key(0));
+        }
+    }
+
+    /// Get reboot reason recorded in boot info.
+    pub fn reboot_reason(&self) -> u8 {
+        self.reg.boot_info.read().reboot_reason()
+    }
+
+    /// Set reboot reason recorded in boot info.
+    pub fn set_reboot_reason(&mut self, reason: u8) {
+        unsafe {
+            // Enable write.
+            self.reg
+                .write_key
+                .modify(|v| v.set_write_key(Self::WRITE_EN_KEY));
+
+            self.reg.boot_info.modify(|v| v.set_reboot_reason(reason));
+
+            // Disable write.
+            self.reg.write_key.modify(|v| v.set_write_key(0));
+        }
+    }
+
     /// Free the RealTime and return RTC instance.
     pub fn free(self, cmu: &Cmu) -> Rtc {
         let clk = &cmu.register_block().clock_rtc;
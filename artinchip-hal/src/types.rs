@@ -1,5 +1,7 @@
 //! Some shared types.
 
 mod ring_buffer;
+mod slice_ring_buffer;
 
 pub use ring_buffer::RingBuffer;
+pub use slice_ring_buffer::SliceRingBuffer;
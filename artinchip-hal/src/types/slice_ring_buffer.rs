@@ -0,0 +1,166 @@
+//! Ring buffer over caller provided storage.
+
+/// A ring buffer of `Copy` items stored in a caller provided slice.
+///
+/// Unlike [`RingBuffer`](super::RingBuffer), the capacity is chosen at runtime
+/// by the length of the storage slice.
+pub struct SliceRingBuffer<'a, T: Copy> {
+    buf: &'a mut [T],
+    head: usize,
+    tail: usize,
+    full: bool,
+}
+
+impl<'a, T: Copy> SliceRingBuffer<'a, T> {
+    /// Create a new empty ring buffer backed by `buf`.
+    pub const fn new(buf: &'a mut [T]) -> Self {
+        Self {
+            buf,
+            head: 0,
+            tail: 0,
+            full: false,
+        }
+    }
+
+    /// Create a ring buffer without storage; every push fails.
+    pub const fn empty() -> Self {
+        Self::new(&mut [])
+    }
+
+    /// Capacity of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Number of items in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        if self.full {
+            self.buf.len()
+        } else if self.head >= self.tail {
+            self.head - self.tail
+        } else {
+            self.buf.len() - self.tail + self.head
+        }
+    }
+
+    /// Check if the buffer is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail && !self.full
+    }
+
+    /// Check if the buffer is full.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.full || self.buf.is_empty()
+    }
+
+    /// Push an item into the buffer.
+    /// Returns `Err(item)` if the buffer is full.
+    #[inline]
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        self.buf[self.head] = item;
+        self.head = (self.head + 1) % self.buf.len();
+        if self.head == self.tail {
+            self.full = true;
+        }
+        Ok(())
+    }
+
+    /// Pop an item from the buffer.
+    /// Returns `None` if the buffer is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let item = self.buf[self.tail];
+        self.tail = (self.tail + 1) % self.buf.len();
+        self.full = false;
+        Some(item)
+    }
+
+    /// Drop all items in the buffer.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.tail = 0;
+        self.full = false;
+    }
+
+    /// Replace the storage with `buf`, returning the previous one.
+    ///
+    /// Items in the buffer are dropped.
+    #[inline]
+    pub fn replace(&mut self, buf: &'a mut [T]) -> &'a mut [T] {
+        self.clear();
+        core::mem::replace(&mut self.buf, buf)
+    }
+}
+
+impl<'a, T: Copy> Default for SliceRingBuffer<'a, T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SliceRingBuffer;
+
+    #[test]
+    fn slice_ring_buffer_push_pop() {
+        let mut storage = [0u8; 3];
+        let mut rb = SliceRingBuffer::new(&mut storage);
+        assert_eq!(rb.capacity(), 3);
+        assert!(rb.is_empty());
+        assert_eq!(rb.pop(), None);
+        assert_eq!(rb.push(1), Ok(()));
+        assert_eq!(rb.push(2), Ok(()));
+        assert_eq!(rb.len(), 2);
+        assert_eq!(rb.pop(), Some(1));
+        assert_eq!(rb.pop(), Some(2));
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn slice_ring_buffer_wrap() {
+        let mut storage = [0u8; 3];
+        let mut rb = SliceRingBuffer::new(&mut storage);
+        for i in 0..10 {
+            assert_eq!(rb.push(i), Ok(()));
+            assert_eq!(rb.push(i + 100), Ok(()));
+            assert_eq!(rb.len(), 2);
+            assert_eq!(rb.pop(), Some(i));
+            assert_eq!(rb.pop(), Some(i + 100));
+        }
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn slice_ring_buffer_full() {
+        let mut storage = [0u8; 2];
+        let mut rb = SliceRingBuffer::new(&mut storage);
+        assert_eq!(rb.push(1), Ok(()));
+        assert_eq!(rb.push(2), Ok(()));
+        assert!(rb.is_full());
+        assert_eq!(rb.len(), 2);
+        assert_eq!(rb.push(3), Err(3));
+        assert_eq!(rb.pop(), Some(1));
+        assert!(!rb.is_full());
+        assert_eq!(rb.push(3), Ok(()));
+        assert_eq!(rb.pop(), Some(2));
+        assert_eq!(rb.pop(), Some(3));
+        assert_eq!(rb.pop(), None);
+
+        let mut empty = SliceRingBuffer::<u8>::empty();
+        assert!(empty.is_full());
+        assert_eq!(empty.push(1), Err(1));
+        assert_eq!(empty.pop(), None);
+    }
+}
@@ -2,15 +2,25 @@
 
 mod blocking;
 mod config;
+mod dma;
+mod error;
 mod instance;
+mod lin;
+mod line_break;
+mod modem;
 #[cfg(feature = "clic_interrupts")]
 mod non_blocking;
 mod pad;
 mod register;
+mod rs485;
+mod setup;
 mod uart_ext;
 
 pub use config::*;
+pub use dma::{CircularDmaReceiver, uart_dma_id};
+pub use error::{ConfigError, Error};
 pub use instance::Uart;
+pub use lin::{LinChecksum, LinError, LinMaster, LinSlave, lin_checksum, lin_protected_id};
 #[cfg(feature = "clic_interrupts")]
 pub use non_blocking::*;
 pub use pad::*;
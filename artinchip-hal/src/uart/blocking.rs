@@ -1,9 +1,13 @@
 //! Blocking serial communication interface.
 
-use super::config::{StopBits, UartConfig};
+use super::config::UartConfig;
+use super::error::{ConfigError, Error, blocking_read_fifo, check_line_status};
 use super::instance::Uart;
 use super::pad::{Receive, Transmit, UartPad};
 use super::register::RegisterBlock;
+use super::setup::{
+    apply_config, clock_register, current_divisor, enable_clock, wait_transmit_idle,
+};
 use crate::cmu::Cmu;
 use uart16550::TriggerLevel;
 
@@ -13,9 +17,12 @@ where
     TX: UartPad<I> + Transmit<I>,
     RX: UartPad<I> + Receive<I>,
 {
-    reg: &'a RegisterBlock,
+    pub(super) reg: &'a RegisterBlock,
     tx: TX,
     rx: RX,
+    rx_error: Option<Error>,
+    // Module clock frequency in Hz.
+    clk: u32,
 }
 
 impl<'a, const I: u8, TX, RX> BlockingSerial<'a, I, TX, RX>
@@ -24,43 +31,15 @@ where
     RX: UartPad<I> + Receive<I>,
 {
     /// Create a new blocking serial.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` can not be applied, e.g. the baud rate is out of
+    /// tolerance. Create the serial with a known good configuration such as
+    /// [`UartConfig::default`] and use [`Self::set_config`] to handle such
+    /// errors.
     pub fn new(reg: &'a RegisterBlock, tx: TX, rx: RX, config: UartConfig, cmu: &mut Cmu) -> Self {
-        // Enable clocks for the UART instance
-        // TODO: flexible clock frequency handling
-        let fix_mod_clk_rate = 48_000_000;
-        let fix_mod_div = 24;
-        let clk = cmu.register_block();
-        let uart_clk = match I {
-            0 => &clk.clock_uart0,
-            1 => &clk.clock_uart1,
-            2 => &clk.clock_uart2,
-            3 => &clk.clock_uart3,
-            4 => &clk.clock_uart4,
-            5 => &clk.clock_uart5,
-            6 => &clk.clock_uart6,
-            7 => &clk.clock_uart7,
-            _ => panic!("Invalid UART index"),
-        };
-        unsafe {
-            // Initialize module clock.
-            // Reference: https://aicdoc.artinchip.com/topics/ic/cmu/cmu-function2-d13x.html#topic_yvp_f24_4bc__table_qb3_bn5_ydc
-            uart_clk.modify(|v| v.set_module_clk_div(fix_mod_div).enable_module_clk());
-            uart_clk.modify(|v| v.enable_bus_clk());
-            uart_clk.modify(|v| v.enable_module_reset());
-            riscv::asm::delay(500);
-            uart_clk.modify(|v| v.disable_module_reset());
-        }
-
-        // Parse configuration
-        let baud_rate = config.baud_rate.0;
-        let data_bits = config.data_bits;
-        let stop_bits = config.stop_bits;
-        let parity = config.parity;
-
-        // Halt uart for configuration
-        unsafe {
-            reg.halt.modify(|v| v.set_halt_change_config_at_busy(true));
-        }
+        let clk = enable_clock::<I>(cmu);
 
         // Disable all interrupts
         let uart16550 = &reg.uart16550;
@@ -73,27 +52,33 @@ where
                 .disable_thre(),
         );
 
-        // Write baud rate divisor
-        let uart_divisor = fix_mod_clk_rate / (16 * baud_rate);
-        uart16550.write_divisor(uart_divisor as u16);
-
-        // Update HALT register to apply configuration
-        unsafe {
-            reg.halt.modify(|v| v.set_halt_change_update(true));
-        }
-
-        // Configure line control register
-        let lcr = uart16550.lcr().read();
-        uart16550.lcr().write(
-            lcr.set_char_len(data_bits.to_char_len())
-                .set_one_stop_bit(stop_bits == StopBits::One)
-                .set_parity(parity.to_parity()),
-        );
+        apply_config::<I, TX, RX>(reg, &config, clk).expect("Invalid UART configuration");
 
         // Enable FIFO and set trigger levels
         uart16550.iir_fcr().write(TriggerLevel::_14.and_reset());
 
-        Self { reg, tx, rx }
+        Self {
+            reg,
+            tx,
+            rx,
+            rx_error: None,
+            clk,
+        }
+    }
+
+    /// Change baud rate, line settings and flow control at runtime.
+    ///
+    /// Waits for queued characters to be sent first. On error the previous
+    /// baud rate and line settings are restored and flow control is unchanged.
+    pub fn set_config(&mut self, config: UartConfig) -> Result<(), ConfigError> {
+        wait_transmit_idle(self.reg);
+        apply_config::<I, TX, RX>(self.reg, &config, self.clk)
+    }
+
+    /// Get the baud rate in use, in Hz.
+    #[inline]
+    pub fn baud_rate(&self) -> u32 {
+        self.clk / (16 * current_divisor(self.reg).max(1) as u32)
     }
 
     /// Blocking write buffer.
@@ -101,8 +86,9 @@ where
         let uart16550 = &self.reg.uart16550;
 
         for &b in buf {
-            // Wait until the transmitter FIFO is not full
-            while !uart16550.lsr().read().is_transmitter_fifo_empty() {
+            // Wait until the transmitter FIFO is not full; poll USR since
+            // reading LSR would clear receive line errors.
+            while !self.reg.usr.read().is_transmit_fifo_not_full() {
                 core::hint::spin_loop();
             }
             uart16550.rbr_thr().tx_data(b);
@@ -111,10 +97,12 @@ where
     }
 
     /// Blocking read buffer.
-    pub fn blocking_read(&mut self, buf: &mut [u8]) -> Result<usize, ()> {
-        // TODO: implement reading into buffer
-        buf.fill(0);
-        Ok(0)
+    ///
+    /// Waits for at least one byte, then drains the receive FIFO into `buf`.
+    /// A line error ends the read; it is returned at once if nothing has been
+    /// read yet, or by the next call otherwise.
+    pub fn blocking_read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        blocking_read_fifo(&self.reg.uart16550, buf, &mut self.rx_error)
     }
 
     /// Statically split into transmit and receive halves.
@@ -125,26 +113,17 @@ where
                 _pad: self.tx,
             },
             ReceiveHalf {
-                _reg: self.reg,
+                reg: self.reg,
                 _pad: self.rx,
+                rx_error: self.rx_error,
             },
         )
     }
 
     /// Free the blocking serial and return UART instance, TX and RX pads.
     pub fn free(self, cmu: &mut Cmu) -> (Uart<I>, TX, RX) {
+        let uart_clk = clock_register::<I>(cmu);
         unsafe {
-            let clk = cmu.register_block();
-            let uart_clk = match I {
-                0 => &clk.clock_uart0,
-                1 => &clk.clock_uart1,
-                2 => &clk.clock_uart2,
-                3 => &clk.clock_uart3,
-                4 => &clk.clock_uart4,
-                5 => &clk.clock_uart5,
-                6 => &clk.clock_uart6,
-                _ => &clk.clock_uart7,
-            };
             uart_clk.modify(|v| {
                 v.disable_bus_clk()
                     .disable_module_clk()
@@ -173,8 +152,9 @@ where
         let uart16550 = &self.reg.uart16550;
 
         for &b in buf {
-            // Wait until the transmitter FIFO is not full
-            while !uart16550.lsr().read().is_transmitter_fifo_empty() {
+            // Wait until the transmitter FIFO is not full; poll USR since
+            // reading LSR would clear receive line errors.
+            while !self.reg.usr.read().is_transmit_fifo_not_full() {
                 core::hint::spin_loop();
             }
             uart16550.rbr_thr().tx_data(b);
@@ -188,8 +168,9 @@ pub struct ReceiveHalf<'a, const I: u8, RX>
 where
     RX: UartPad<I> + Receive<I>,
 {
-    _reg: &'a RegisterBlock,
+    reg: &'a RegisterBlock,
     _pad: RX,
+    rx_error: Option<Error>,
 }
 
 impl<'a, const I: u8, RX> ReceiveHalf<'a, I, RX>
@@ -197,10 +178,12 @@ where
     RX: UartPad<I> + Receive<I>,
 {
     /// Blocking read buffer.
-    pub fn blocking_read(&mut self, buf: &mut [u8]) -> Result<usize, ()> {
-        // TODO: implement reading into buffer
-        buf.fill(0);
-        Ok(0)
+    ///
+    /// Waits for at least one byte, then drains the receive FIFO into `buf`.
+    /// A line error ends the read; it is returned at once if nothing has been
+    /// read yet, or by the next call otherwise.
+    pub fn blocking_read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        blocking_read_fifo(&self.reg.uart16550, buf, &mut self.rx_error)
     }
 }
 
@@ -209,7 +192,7 @@ where
     TX: UartPad<I> + Transmit<I>,
     RX: UartPad<I> + Receive<I>,
 {
-    type Error = core::convert::Infallible;
+    type Error = Error;
 }
 
 impl<'a, const I: u8, TX> embedded_io::ErrorType for TransmitHalf<'a, I, TX>
@@ -223,7 +206,7 @@ impl<'a, const I: u8, RX> embedded_io::ErrorType for ReceiveHalf<'a, I, RX>
 where
     RX: UartPad<I> + Receive<I>,
 {
-    type Error = core::convert::Infallible;
+    type Error = Error;
 }
 
 impl<'a, const I: u8, TX, RX> embedded_io::Write for BlockingSerial<'a, I, TX, RX>
@@ -270,8 +253,7 @@ where
 {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        self.blocking_read(buf).ok();
-        Ok(buf.len())
+        self.blocking_read(buf)
     }
 }
 
@@ -281,7 +263,50 @@ where
 {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        self.blocking_read(buf).ok();
-        Ok(buf.len())
+        self.blocking_read(buf)
+    }
+}
+
+impl<'a, const I: u8, TX, RX> embedded_io::ReadReady for BlockingSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        let ready = check_line_status(&self.reg.uart16550, &mut self.rx_error);
+        Ok(ready || self.rx_error.is_some())
+    }
+}
+
+impl<'a, const I: u8, RX> embedded_io::ReadReady for ReceiveHalf<'a, I, RX>
+where
+    RX: UartPad<I> + Receive<I>,
+{
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        let ready = check_line_status(&self.reg.uart16550, &mut self.rx_error);
+        Ok(ready || self.rx_error.is_some())
+    }
+}
+
+impl<'a, const I: u8, TX, RX> embedded_io::WriteReady for BlockingSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    #[inline]
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.reg.usr.read().is_transmit_fifo_not_full())
+    }
+}
+
+impl<'a, const I: u8, TX> embedded_io::WriteReady for TransmitHalf<'a, I, TX>
+where
+    TX: UartPad<I> + Transmit<I>,
+{
+    #[inline]
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.reg.usr.read().is_transmit_fifo_not_full())
     }
 }
@@ -48,11 +48,50 @@ impl Parity {
     }
 }
 
+/// RS-485 half-duplex configuration.
+///
+/// The DE output is driven by hardware around each transmitted frame. Applied
+/// with `enable_rs485`, available when the transmit pad is paired with a
+/// [`DriverEnable`](super::DriverEnable) pad.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Rs485Config {
+    /// Time from DE assertion to the start bit, in serial clock cycles (0..=15).
+    pub de_assert_time: u8,
+    /// Time from the stop bit to DE de-assertion, in serial clock cycles (0..=15).
+    pub de_deassert_time: u8,
+    /// Use 9-bit frames, so address frames can be sent with `send_address`.
+    ///
+    /// Frames carry 8 data bits and the address flag in place of the parity
+    /// bit, replacing the configured data bits and parity.
+    pub nine_bit: bool,
+    /// Receive only frames following a 9-bit address frame that matches.
+    ///
+    /// Implies [`Self::nine_bit`]; `None` receives all frames.
+    pub address: Option<u8>,
+    /// Bus idle time before transmitting, in units of 8 × 16 serial clock
+    /// cycles (1..=63); `None` disables bus idle detection.
+    pub bus_idle_time: Option<u8>,
+}
+
+impl Default for Rs485Config {
+    fn default() -> Self {
+        Self {
+            de_assert_time: 1,
+            de_deassert_time: 1,
+            nine_bit: false,
+            address: None,
+            bus_idle_time: None,
+        }
+    }
+}
+
 pub struct UartConfig {
     pub baud_rate: Baud,
     pub data_bits: DataBits,
     pub stop_bits: StopBits,
     pub parity: Parity,
+    /// Maximum deviation of the achieved baud rate, in permille.
+    pub baud_tolerance_permille: u16,
 }
 
 impl Default for UartConfig {
@@ -62,6 +101,7 @@ impl Default for UartConfig {
             data_bits: DataBits::Eight,
             stop_bits: StopBits::One,
             parity: Parity::None,
+            baud_tolerance_permille: 20,
         }
     }
 }
@@ -0,0 +1,582 @@
+//! UART DMA transfers.
+//!
+//! Buffers and task descriptors are written back from or invalidated in the
+//! data cache by these functions. Receive buffers must start and end on a
+//! cache line boundary, otherwise [`Error::Alignment`] is returned, so that
+//! invalidating them cannot discard neighbouring data.
+
+use super::blocking::BlockingSerial;
+use super::error::Error;
+use super::pad::{Receive, Transmit, UartPad};
+use super::register::{DmaHandshakeMode, RegisterBlock};
+use crate::dma::cache;
+use crate::dma::{BurstSize, ChConfig, ChMode, DataWidth, DmaChannel, DmaTask, HandshakeMode};
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::instance::{Uart, UartInterrupt},
+    super::non_blocking::{AsyncSerial, flush_buffered},
+    crate::dma::DmaEvents,
+};
+
+/// DMA device ID of SRAM.
+const DMA_ID_SRAM: u8 = 0;
+
+/// DMA handshake device ID of UART instance `I` (`DMA_ID_UART0` + `I`).
+#[inline]
+pub const fn uart_dma_id<const I: u8>() -> u8 {
+    18 + I
+}
+
+#[inline]
+fn data_register(reg: &RegisterBlock) -> u32 {
+    // RBR and THR share offset 0.
+    &reg.uart16550 as *const _ as u32
+}
+
+#[inline]
+fn enable_dma_handshake(reg: &RegisterBlock) {
+    unsafe {
+        reg.hsk
+            .modify(|v| v.set_handshake_mode(DmaHandshakeMode::Handshake));
+        reg.halt
+            .modify(|v| v.set_dma_pte_tx(true).set_dma_pte_rx(true));
+    }
+}
+
+/// Build a memory to UART transmit task.
+fn tx_task<const I: u8>(reg: &RegisterBlock, buf: &[u8]) -> DmaTask {
+    DmaTask {
+        cfg: ChConfig::zeroed()
+            .set_src_dev(DMA_ID_SRAM)
+            .set_src_data_width(DataWidth::Bits8)
+            .set_src_burst(BurstSize::Burst1)
+            .enable_src_addr_inc()
+            .set_snk_dev(uart_dma_id::<I>())
+            .set_snk_data_width(DataWidth::Bits8)
+            .set_snk_burst(BurstSize::Burst1)
+            .disable_snk_addr_inc(),
+        src: buf.as_ptr() as u32,
+        dst: data_register(reg),
+        len: buf.len() as u32,
+        delay: DmaTask::DEFAULT_DELAY,
+        p_next: DmaTask::TASK_END,
+        mode: ChMode::zeroed()
+            .set_src_mode(HandshakeMode::Wait)
+            .set_snk_mode(HandshakeMode::Handshake),
+        v_next: None,
+    }
+}
+
+/// Build a UART to memory receive task.
+fn rx_task<const I: u8>(reg: &RegisterBlock, buf: &mut [u8]) -> DmaTask {
+    DmaTask {
+        cfg: ChConfig::zeroed()
+            .set_src_dev(uart_dma_id::<I>())
+            .set_src_data_width(DataWidth::Bits8)
+            .set_src_burst(BurstSize::Burst1)
+            .disable_src_addr_inc()
+            .set_snk_dev(DMA_ID_SRAM)
+            .set_snk_data_width(DataWidth::Bits8)
+            .set_snk_burst(BurstSize::Burst1)
+            .enable_snk_addr_inc(),
+        src: data_register(reg),
+        dst: buf.as_mut_ptr() as u32,
+        len: buf.len() as u32,
+        delay: DmaTask::DEFAULT_DELAY,
+        p_next: DmaTask::TASK_END,
+        mode: ChMode::zeroed()
+            .set_src_mode(HandshakeMode::Handshake)
+            .set_snk_mode(HandshakeMode::Wait),
+        v_next: None,
+    }
+}
+
+/// Reject receive buffers that share a cache line with other data.
+#[inline]
+fn check_rx_buffer(buf: &[u8]) -> Result<(), Error> {
+    if cache::is_line_aligned(buf.as_ptr() as usize, buf.len()) {
+        Ok(())
+    } else {
+        Err(Error::Alignment)
+    }
+}
+
+#[inline]
+fn start_task<const C: u8>(ch: &mut DmaChannel<'_, C>, task: &DmaTask) {
+    cache::clean_invalidate(task as *const _ as usize, size_of::<DmaTask>());
+    ch.start(task);
+}
+
+fn blocking_transfer<const C: u8>(ch: &mut DmaChannel<'_, C>, task: &DmaTask) -> Result<(), Error> {
+    start_task(ch, task);
+    loop {
+        if ch.is_addr_req_error_pending() {
+            ch.stop();
+            ch.clear_all_pending();
+            return Err(Error::Dma);
+        }
+        if ch.is_all_finish_pending() {
+            ch.clear_all_pending();
+            return Ok(());
+        }
+        core::hint::spin_loop();
+    }
+}
+
+impl<'a, const I: u8, TX, RX> BlockingSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    /// Write `buf` through DMA channel `ch`, blocking until it is queued in
+    /// the transmit FIFO.
+    ///
+    /// Polls the channel status; do not bind `DmaInterruptHandler` while using
+    /// the blocking DMA functions. Fails with [`Error::Dma`] on a DMA address
+    /// or request error.
+    pub fn write_dma<const C: u8>(
+        &mut self,
+        ch: &mut DmaChannel<'_, C>,
+        buf: &[u8],
+    ) -> Result<(), Error> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        enable_dma_handshake(self.reg);
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        blocking_transfer(ch, &tx_task::<I>(self.reg, buf))
+    }
+
+    /// Fill `buf` through DMA channel `ch`, blocking until it is full.
+    ///
+    /// Line errors are not reported in DMA mode; a DMA address or request
+    /// error fails with [`Error::Dma`]. `buf` must be cache line aligned and
+    /// sized, otherwise [`Error::Alignment`] is returned.
+    pub fn read_dma<const C: u8>(
+        &mut self,
+        ch: &mut DmaChannel<'_, C>,
+        buf: &mut [u8],
+    ) -> Result<(), Error> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        check_rx_buffer(buf)?;
+        enable_dma_handshake(self.reg);
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        let task = rx_task::<I>(self.reg, buf);
+        let result = blocking_transfer(ch, &task);
+        cache::invalidate(buf.as_ptr() as usize, buf.len());
+        result
+    }
+
+    /// Start continuous reception into `buf` through DMA channel `ch`.
+    ///
+    /// `task` holds the self-linked descriptor and must stay in place while
+    /// the receiver exists. `buf` must be cache line aligned and sized,
+    /// otherwise [`Error::Alignment`] is returned.
+    pub fn receive_circular_dma<'c, const C: u8>(
+        &'c mut self,
+        ch: &'c mut DmaChannel<'static, C>,
+        buf: &'c mut [u8],
+        task: &'c mut DmaTask,
+    ) -> Result<CircularDmaReceiver<'c, C>, Error> {
+        check_rx_buffer(buf)?;
+        Ok(CircularDmaReceiver::start::<I>(
+            self.reg, ch, buf, task, false,
+        ))
+    }
+}
+
+/// Stops the channel if a transfer future is dropped before completion.
+#[cfg(feature = "clic_interrupts")]
+struct StopOnDrop<'c, 'd, const C: u8> {
+    ch: &'c mut DmaChannel<'d, C>,
+    done: bool,
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'c, 'd, const C: u8> Drop for StopOnDrop<'c, 'd, C> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.ch.stop();
+        }
+    }
+}
+
+/// Re-enables the receive interrupt when a DMA read ends or is dropped.
+#[cfg(feature = "clic_interrupts")]
+struct RestoreRda<'r> {
+    reg: &'r RegisterBlock,
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl Drop for RestoreRda<'_> {
+    fn drop(&mut self) {
+        let uart16550 = &self.reg.uart16550;
+        let ier = uart16550.ier().read();
+        uart16550.ier().write(ier.enable_rda());
+    }
+}
+
+#[cfg(feature = "clic_interrupts")]
+async fn async_transfer<const C: u8>(
+    ch: &mut DmaChannel<'_, C>,
+    task: &DmaTask,
+) -> Result<(), Error> {
+    ch.take_events();
+    start_task(ch, task);
+    let mut guard = StopOnDrop { ch, done: false };
+    let events = guard
+        .ch
+        .wait_for_events(DmaEvents::ALL_FINISH.union(DmaEvents::ADDR_REQ_ERROR))
+        .await;
+    if events.intersects(DmaEvents::ADDR_REQ_ERROR) {
+        // The guard stops the channel.
+        return Err(Error::Dma);
+    }
+    guard.done = true;
+    Ok(())
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'a, const I: u8, TX, RX> AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    /// Write `buf` through DMA channel `ch`.
+    ///
+    /// Data buffered by `embedded_io_async::Write` is flushed first. Requires
+    /// `DmaInterruptHandler` bound to the `DMA` interrupt. Fails with
+    /// [`Error::Dma`] on a DMA address or request error.
+    pub async fn write_dma<const C: u8>(
+        &mut self,
+        ch: &mut DmaChannel<'_, C>,
+        buf: &[u8],
+    ) -> Result<(), Error> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        flush_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state()).await;
+        enable_dma_handshake(self.reg);
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        let task = tx_task::<I>(self.reg, buf);
+        async_transfer(ch, &task).await
+    }
+
+    /// Fill `buf` through DMA channel `ch`.
+    ///
+    /// The receive interrupt is disabled during the transfer, also if the
+    /// future is dropped. Line errors are not reported in DMA mode; a DMA
+    /// address or request error fails with [`Error::Dma`]. `buf` must be
+    /// cache line aligned and sized, otherwise [`Error::Alignment`] is
+    /// returned.
+    pub async fn read_dma<const C: u8>(
+        &mut self,
+        ch: &mut DmaChannel<'_, C>,
+        buf: &mut [u8],
+    ) -> Result<(), Error> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        check_rx_buffer(buf)?;
+        let uart16550 = &self.reg.uart16550;
+        let ier = uart16550.ier().read();
+        uart16550.ier().write(ier.disable_rda());
+        let _rda = RestoreRda { reg: self.reg };
+        enable_dma_handshake(self.reg);
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        let task = rx_task::<I>(self.reg, buf);
+        let result = async_transfer(ch, &task).await;
+        cache::invalidate(buf.as_ptr() as usize, buf.len());
+        result
+    }
+
+    /// Start continuous reception into `buf` through DMA channel `ch`.
+    ///
+    /// The receive interrupt is disabled until the receiver is dropped. `buf`
+    /// must be cache line aligned and sized, otherwise [`Error::Alignment`]
+    /// is returned.
+    pub fn receive_circular_dma<'c, const C: u8>(
+        &'c mut self,
+        ch: &'c mut DmaChannel<'static, C>,
+        buf: &'c mut [u8],
+        task: &'c mut DmaTask,
+    ) -> Result<CircularDmaReceiver<'c, C>, Error> {
+        check_rx_buffer(buf)?;
+        let uart16550 = &self.reg.uart16550;
+        let ier = uart16550.ier().read();
+        uart16550.ier().write(ier.disable_rda());
+        Ok(CircularDmaReceiver::start::<I>(
+            self.reg, ch, buf, task, true,
+        ))
+    }
+}
+
+/// Continuous UART reception into a circular buffer through DMA.
+///
+/// The DMA controller loops over `buf` with a self-linked task; read often
+/// enough that it never laps the reader. Laps are counted from the write
+/// position and the channel's task finish flag, which records one finished
+/// lap at most: [`Error::Overrun`] is returned if the writer is found a lap
+/// or more ahead, but an overrun by two or more laps between reads may go
+/// unnoticed.
+pub struct CircularDmaReceiver<'c, const C: u8> {
+    reg: &'c RegisterBlock,
+    ch: &'c mut DmaChannel<'static, C>,
+    buf: &'c mut [u8],
+    laps: LapState,
+    // Task finish event consumed while waiting, not yet counted.
+    pending_finish: bool,
+    restore_rda: bool,
+}
+
+/// Read position and lap accounting of a circular receiver.
+#[derive(Default)]
+struct LapState {
+    // Read position within the buffer.
+    tail: usize,
+    // Last write position reported by the channel.
+    last_head: usize,
+    // Number of times the writer wrapped since the reader last did.
+    laps: usize,
+    // A lap was counted from the write position before its finish flag was seen.
+    unflagged: bool,
+}
+
+impl LapState {
+    /// Account a sample of the write position in a buffer of `len` bytes and
+    /// return the number of unread bytes.
+    ///
+    /// `before` and `head` are the write positions read before and after the
+    /// task finish flag was taken; `finished` is the flag.
+    fn update(
+        &mut self,
+        len: usize,
+        before: usize,
+        finished: bool,
+        head: usize,
+    ) -> Result<usize, Error> {
+        let wrapped = head < self.last_head;
+        // A wrap after the flag was sampled is not in `finished` yet.
+        let late_wrap = head < before;
+        if wrapped {
+            self.laps += 1;
+        }
+        if finished {
+            if self.unflagged {
+                // The flag of the lap already counted from the position.
+                self.unflagged = false;
+            } else if !wrapped || late_wrap {
+                // The writer finished a lap the position does not show, so it
+                // passed the last position again.
+                self.laps += 1;
+            }
+        }
+        if wrapped && (late_wrap || !finished) {
+            self.unflagged = true;
+        }
+        self.last_head = head;
+        let pending = self.laps * len + head - self.tail;
+        if pending > len {
+            // The writer lapped the reader; resynchronize at the write position.
+            self.tail = head;
+            self.laps = 0;
+            return Err(Error::Overrun);
+        }
+        Ok(pending)
+    }
+
+    /// Advance the read position by `count` bytes, at most to the end of a
+    /// buffer of `len` bytes.
+    fn consume(&mut self, len: usize, count: usize) {
+        self.tail += count;
+        if self.tail == len {
+            self.tail = 0;
+            self.laps -= 1;
+        }
+    }
+}
+
+impl<'c, const C: u8> CircularDmaReceiver<'c, C> {
+    fn start<const I: u8>(
+        reg: &'c RegisterBlock,
+        ch: &'c mut DmaChannel<'static, C>,
+        buf: &'c mut [u8],
+        task: &'c mut DmaTask,
+        restore_rda: bool,
+    ) -> Self {
+        assert!(!buf.is_empty(), "Circular DMA buffer must not be empty");
+        enable_dma_handshake(reg);
+        cache::clean_invalidate(buf.as_ptr() as usize, buf.len());
+        *task = rx_task::<I>(reg, buf);
+        task.p_next = task as *const DmaTask as u32;
+        #[cfg(feature = "clic_interrupts")]
+        ch.take_events();
+        start_task(ch, task);
+        Self {
+            reg,
+            ch,
+            buf,
+            laps: LapState::default(),
+            pending_finish: false,
+            restore_rda,
+        }
+    }
+
+    /// Take the task finish flag, raised each time the writer ends a lap.
+    fn take_lap_end(&mut self) -> bool {
+        let mut finished = core::mem::take(&mut self.pending_finish);
+        finished |= self.ch.is_task_finish_pending();
+        if finished {
+            self.ch.clear_task_finish_pending();
+        }
+        #[cfg(feature = "clic_interrupts")]
+        {
+            finished |= self.ch.take_events().intersects(DmaEvents::TASK_FINISH);
+        }
+        finished
+    }
+
+    /// Write position within `buf`.
+    fn head(&self) -> usize {
+        let len = self.buf.len();
+        let head = len - (self.ch.bcnt_left() as usize).min(len);
+        // End of the task before reload; count it as the start of the next lap.
+        if head == len { 0 } else { head }
+    }
+
+    /// Number of received bytes not read yet.
+    pub fn available(&mut self) -> Result<usize, Error> {
+        let len = self.buf.len();
+        let before = self.head();
+        let finished = self.take_lap_end();
+        let head = self.head();
+        self.laps.update(len, before, finished, head)
+    }
+
+    /// Copy received bytes into `out` without blocking, returning the count.
+    pub fn read_available(&mut self, out: &mut [u8]) -> Result<usize, Error> {
+        let len = self.buf.len();
+        let count = self.available()?.min(out.len());
+        let mut done = 0;
+        while done < count {
+            let tail = self.laps.tail;
+            let chunk = (count - done).min(len - tail);
+            let src = &self.buf[tail..tail + chunk];
+            cache::invalidate(src.as_ptr() as usize, chunk);
+            out[done..done + chunk].copy_from_slice(src);
+            done += chunk;
+            self.laps.consume(len, chunk);
+        }
+        Ok(count)
+    }
+
+    /// Block until at least one byte is received, then copy received bytes
+    /// into `out`.
+    pub fn blocking_read(&mut self, out: &mut [u8]) -> Result<usize, Error> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let count = self.read_available(out)?;
+            if count > 0 {
+                return Ok(count);
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Wait until received bytes are reported at half or full buffer, then
+    /// copy them into `out`.
+    ///
+    /// Requires `DmaInterruptHandler` bound to the `DMA` interrupt.
+    #[cfg(feature = "clic_interrupts")]
+    pub async fn read(&mut self, out: &mut [u8]) -> Result<usize, Error> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let count = self.read_available(out)?;
+            if count > 0 {
+                return Ok(count);
+            }
+            let events = self
+                .ch
+                .wait_for_events(DmaEvents::TASK_HALF.union(DmaEvents::TASK_FINISH))
+                .await;
+            // The events are consumed here; keep a lap end for `available`.
+            self.pending_finish |= events.intersects(DmaEvents::TASK_FINISH);
+        }
+    }
+}
+
+impl<'c, const C: u8> Drop for CircularDmaReceiver<'c, C> {
+    fn drop(&mut self) {
+        self.ch.stop();
+        if self.restore_rda {
+            let uart16550 = &self.reg.uart16550;
+            let ier = uart16550.ier().read();
+            uart16550.ier().write(ier.enable_rda());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, LapState};
+
+    const LEN: usize = 8;
+
+    #[test]
+    fn lap_state_partial_lap() {
+        let mut s = LapState::default();
+        assert_eq!(s.update(LEN, 0, false, 0), Ok(0));
+        assert_eq!(s.update(LEN, 3, false, 3), Ok(3));
+        s.consume(LEN, 3);
+        assert_eq!(s.update(LEN, 5, false, 5), Ok(2));
+    }
+
+    #[test]
+    fn lap_state_full_lap_from_finish_flag() {
+        // The writer ends exactly one lap; only the finish flag shows it.
+        let mut s = LapState::default();
+        assert_eq!(s.update(LEN, 0, true, 0), Ok(LEN));
+        s.consume(LEN, LEN);
+        assert_eq!(s.update(LEN, 0, false, 0), Ok(0));
+    }
+
+    #[test]
+    fn lap_state_wrap_before_flag() {
+        let mut s = LapState::default();
+        assert_eq!(s.update(LEN, 6, false, 6), Ok(6));
+        s.consume(LEN, 6);
+        // Wrap seen from the position, the finish flag follows later.
+        assert_eq!(s.update(LEN, 2, false, 2), Ok(4));
+        s.consume(LEN, 2);
+        assert_eq!(s.update(LEN, 3, true, 3), Ok(3));
+        s.consume(LEN, 3);
+        assert_eq!(s.update(LEN, 3, false, 3), Ok(0));
+    }
+
+    #[test]
+    fn lap_state_wrap_with_flag() {
+        let mut s = LapState::default();
+        assert_eq!(s.update(LEN, 6, false, 6), Ok(6));
+        s.consume(LEN, 6);
+        assert_eq!(s.update(LEN, 1, true, 1), Ok(3));
+        s.consume(LEN, 2);
+        assert_eq!(s.update(LEN, 1, false, 1), Ok(1));
+    }
+
+    #[test]
+    fn lap_state_overrun() {
+        let mut s = LapState::default();
+        assert_eq!(s.update(LEN, 4, false, 4), Ok(4));
+        // A whole lap more at the same position.
+        assert_eq!(s.update(LEN, 4, true, 4), Err(Error::Overrun));
+        assert_eq!(s.update(LEN, 4, false, 4), Ok(0));
+        assert_eq!(s.update(LEN, 6, false, 6), Ok(2));
+    }
+}
@@ -0,0 +1,152 @@
+//! UART errors.
+
+use uart16550::Uart16550;
+
+/// UART receive line error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Receive FIFO overrun; incoming data was lost.
+    Overrun,
+    /// Parity check of a received character failed.
+    Parity,
+    /// A received character had no valid stop bit.
+    Framing,
+    /// Receive line held low for longer than a full character.
+    Break,
+    /// DMA address or request error.
+    Dma,
+    /// DMA receive buffer does not start and end on a cache line boundary.
+    Alignment,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Overrun => f.write_str("receive FIFO overrun"),
+            Error::Parity => f.write_str("parity error"),
+            Error::Framing => f.write_str("framing error"),
+            Error::Break => f.write_str("break condition"),
+            Error::Dma => f.write_str("DMA transfer error"),
+            Error::Alignment => f.write_str("DMA buffer not cache line aligned"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl embedded_io::Error for Error {
+    #[inline]
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            Error::Overrun | Error::Break | Error::Dma => embedded_io::ErrorKind::Other,
+            Error::Parity | Error::Framing => embedded_io::ErrorKind::InvalidData,
+            Error::Alignment => embedded_io::ErrorKind::InvalidInput,
+        }
+    }
+}
+
+/// UART configuration error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// The closest achievable baud rate deviates more than the configured tolerance.
+    BaudRate {
+        /// Requested baud rate.
+        requested: u32,
+        /// Closest baud rate the module clock can generate.
+        achieved: u32,
+    },
+    /// The hardware did not apply the new configuration in time.
+    UpdateTimeout,
+    /// Address frames were requested without RS-485 9-bit frames enabled.
+    NotNineBit,
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::BaudRate {
+                requested,
+                achieved,
+            } => write!(
+                f,
+                "baud rate {requested} not achievable (closest {achieved})"
+            ),
+            ConfigError::UpdateTimeout => f.write_str("configuration update timed out"),
+            ConfigError::NotNineBit => f.write_str("9-bit frames not enabled"),
+        }
+    }
+}
+
+impl core::error::Error for ConfigError {}
+
+/// Read the line status and record any line error into `rx_error`.
+///
+/// Reading LSR clears its error flags, so every LSR read on the receive path
+/// must go through this function. A character received with a parity, framing
+/// or break error is discarded. Returns whether valid data is ready.
+#[inline]
+pub(crate) fn check_line_status(uart16550: &Uart16550<u32>, rx_error: &mut Option<Error>) -> bool {
+    let lsr = uart16550.lsr().read();
+    let error = if lsr.is_break_interrupt() {
+        Some(Error::Break)
+    } else if lsr.is_framing_error() {
+        Some(Error::Framing)
+    } else if lsr.is_parity_error() {
+        Some(Error::Parity)
+    } else if lsr.is_overrun_error() {
+        Some(Error::Overrun)
+    } else {
+        None
+    };
+    match error {
+        Some(Error::Overrun) => {
+            rx_error.get_or_insert(Error::Overrun);
+            lsr.is_data_ready()
+        }
+        Some(e) => {
+            if lsr.is_data_ready() {
+                // Drop the corrupted character at the head of the FIFO.
+                uart16550.rbr_thr().rx_data();
+            }
+            rx_error.get_or_insert(e);
+            false
+        }
+        None => lsr.is_data_ready(),
+    }
+}
+
+/// Drain the receive FIFO into `buf`, waiting for at least one character.
+///
+/// A line error ends the read; it is returned at once if nothing has been read
+/// yet, or kept in `rx_error` for the next call otherwise.
+pub(crate) fn blocking_read_fifo(
+    uart16550: &Uart16550<u32>,
+    buf: &mut [u8],
+    rx_error: &mut Option<Error>,
+) -> Result<usize, Error> {
+    if buf.is_empty() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    loop {
+        let ready = check_line_status(uart16550, rx_error);
+        if let Some(e) = *rx_error {
+            if count == 0 {
+                *rx_error = None;
+                return Err(e);
+            }
+            return Ok(count);
+        }
+        if ready {
+            buf[count] = uart16550.rbr_thr().rx_data();
+            count += 1;
+            if count == buf.len() {
+                return Ok(count);
+            }
+        } else if count > 0 {
+            return Ok(count);
+        } else {
+            core::hint::spin_loop();
+        }
+    }
+}
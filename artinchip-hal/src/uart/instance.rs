@@ -14,6 +14,8 @@ use {super::non_blocking::*, crate::interrupt::clic::typelevel};
 #[cfg(feature = "clic_interrupts")]
 pub trait UartInterrupt<const I: u8> {
     type Interrupt: typelevel::Interrupt;
+    /// Async state of this instance.
+    fn state() -> &'static AsyncState;
 }
 
 // Macro to quickly map instance numbers to interrupt types
@@ -23,6 +25,11 @@ macro_rules! impl_uart_interrupts {
         $(
             impl UartInterrupt<$inst> for Uart<$inst> {
                 type Interrupt = crate::interrupt::clic::typelevel::$irq_type;
+                #[inline]
+                fn state() -> &'static AsyncState {
+                    static STATE: AsyncState = AsyncState::new();
+                    &STATE
+                }
             }
         )*
     };
@@ -96,6 +103,7 @@ impl<const I: u8> UartExt<'static, I> for Uart<I> {
         self,
         tx: TX,
         rx: RX,
+        buffers: AsyncBuffers,
         config: UartConfig,
         cmu: &mut Cmu,
         _irqs: IRQS,
@@ -112,6 +120,6 @@ impl<const I: u8> UartExt<'static, I> for Uart<I> {
                 AsyncUartHandler<I>,
             >,
     {
-        AsyncSerial::new(self.register_block(), tx, rx, config, cmu)
+        AsyncSerial::new(self.register_block(), tx, rx, buffers, config, cmu)
     }
 }
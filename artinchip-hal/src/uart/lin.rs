@@ -0,0 +1,416 @@
+//! Local Interconnect Network (LIN) on top of a blocking serial.
+//!
+//! The bus transceiver echoes every transmitted byte, which is read back to
+//! detect collisions. Configure the serial for 8N1 at the bus baud rate.
+//!
+//! Header and response timeouts follow the LIN maximum frame times, 1.4
+//! times the nominal length, measured on a GTC [`TimerDelay`].
+
+use embedded_io::ReadReady;
+use embedded_time::duration::Milliseconds;
+
+use super::blocking::BlockingSerial;
+use super::error::Error;
+use super::pad::{Receive, Transmit, UartPad};
+use crate::gtc::TimerDelay;
+
+/// Sync byte following the break of a LIN header.
+const LIN_SYNC: u8 = 0x55;
+/// Nominal header length in bit times: break, delimiter, sync and identifier.
+const LIN_HEADER_BITS: u64 = 34;
+/// Maximum response length.
+const LIN_MAX_DATA: usize = 8;
+
+/// LIN checksum model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinChecksum {
+    /// Sum over the data bytes only (LIN 1.x, diagnostic frames).
+    Classic,
+    /// Sum over the protected identifier and the data bytes (LIN 2.x).
+    Enhanced,
+}
+
+/// LIN error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinError {
+    /// Receive line error.
+    Uart(Error),
+    /// No byte received in time.
+    Timeout,
+    /// A break was not followed by the sync byte.
+    Sync,
+    /// Protected identifier parity mismatch.
+    Parity,
+    /// Response checksum mismatch.
+    Checksum,
+    /// A transmitted byte was not read back unchanged; another node drove the bus.
+    Readback,
+    /// Frame ID out of range (expected 0..=63).
+    Id,
+    /// Response length out of range (expected 1..=8).
+    Length,
+}
+
+impl core::fmt::Display for LinError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LinError::Uart(e) => write!(f, "LIN line error: {e}"),
+            LinError::Timeout => f.write_str("LIN timeout"),
+            LinError::Sync => f.write_str("LIN sync byte mismatch"),
+            LinError::Parity => f.write_str("LIN identifier parity error"),
+            LinError::Checksum => f.write_str("LIN checksum error"),
+            LinError::Readback => f.write_str("LIN readback mismatch"),
+            LinError::Id => f.write_str("LIN frame ID out of range"),
+            LinError::Length => f.write_str("LIN response length out of range"),
+        }
+    }
+}
+
+impl core::error::Error for LinError {}
+
+impl From<Error> for LinError {
+    #[inline]
+    fn from(e: Error) -> Self {
+        LinError::Uart(e)
+    }
+}
+
+/// Compute the protected identifier of frame `id`, adding the parity bits.
+///
+/// Fails with [`LinError::Id`] if `id` is not in 0..=63.
+#[inline]
+pub const fn lin_protected_id(id: u8) -> Result<u8, LinError> {
+    if id >= 64 {
+        return Err(LinError::Id);
+    }
+    let p0 = (id ^ (id >> 1) ^ (id >> 2) ^ (id >> 4)) & 1;
+    let p1 = !((id >> 1) ^ (id >> 3) ^ (id >> 4) ^ (id >> 5)) & 1;
+    Ok(id | (p0 << 6) | (p1 << 7))
+}
+
+/// Check that a response has 1 to 8 data bytes.
+#[inline]
+fn check_length(len: usize) -> Result<(), LinError> {
+    if (1..=LIN_MAX_DATA).contains(&len) {
+        Ok(())
+    } else {
+        Err(LinError::Length)
+    }
+}
+
+/// Compute the checksum of a response with protected identifier `pid`.
+pub fn lin_checksum(model: LinChecksum, pid: u8, data: &[u8]) -> u8 {
+    let init = match model {
+        LinChecksum::Classic => 0,
+        LinChecksum::Enhanced => pid as u16,
+    };
+    let sum = data.iter().fold(init, |sum, &b| {
+        let sum = sum + b as u16;
+        // Add the carry back in.
+        if sum > 0xFF { sum - 0xFF } else { sum }
+    });
+    !(sum as u8)
+}
+
+/// GTC tick after which waiting for a byte fails.
+#[derive(Clone, Copy)]
+struct Deadline<'t> {
+    timer: &'t TimerDelay<'t>,
+    tick: u64,
+}
+
+impl<'t> Deadline<'t> {
+    /// Deadline `ticks` from now.
+    #[inline]
+    fn after(timer: &'t TimerDelay<'t>, ticks: u64) -> Self {
+        Self {
+            timer,
+            tick: timer.get_tick() + ticks,
+        }
+    }
+
+    /// Deadline of 1.4 times `bits` bit times at `baud` from now.
+    fn after_bits(timer: &'t TimerDelay<'t>, baud: u32, bits: u64) -> Self {
+        let ticks = (bits * 14 * timer.tick_freq() as u64).div_ceil(10 * baud.max(1) as u64);
+        Self::after(timer, ticks)
+    }
+
+    #[inline]
+    fn is_expired(&self) -> bool {
+        self.timer.get_tick() >= self.tick
+    }
+}
+
+/// Read one byte, waiting until `deadline` if given.
+fn read_byte<const I: u8, TX, RX>(
+    serial: &mut BlockingSerial<'_, I, TX, RX>,
+    deadline: Option<Deadline<'_>>,
+) -> Result<u8, LinError>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    while !serial.read_ready()? {
+        if deadline.is_some_and(|d| d.is_expired()) {
+            return Err(LinError::Timeout);
+        }
+        core::hint::spin_loop();
+    }
+    let mut byte = [0];
+    serial.blocking_read(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// Write `bytes` and check that the bus echoes them unchanged before `deadline`.
+fn write_checked<const I: u8, TX, RX>(
+    serial: &mut BlockingSerial<'_, I, TX, RX>,
+    bytes: &[u8],
+    deadline: Deadline<'_>,
+) -> Result<(), LinError>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    for &b in bytes {
+        serial.blocking_write(&[b]).ok();
+        if read_byte(serial, Some(deadline))? != b {
+            return Err(LinError::Readback);
+        }
+    }
+    Ok(())
+}
+
+/// Deadline of a response of `len` data bytes and the checksum.
+#[inline]
+fn response_deadline<'t, const I: u8, TX, RX>(
+    serial: &BlockingSerial<'_, I, TX, RX>,
+    timer: &'t TimerDelay<'t>,
+    len: usize,
+) -> Deadline<'t>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    Deadline::after_bits(timer, serial.baud_rate(), 10 * (len as u64 + 1))
+}
+
+fn send_response<const I: u8, TX, RX>(
+    serial: &mut BlockingSerial<'_, I, TX, RX>,
+    timer: &TimerDelay<'_>,
+    pid: u8,
+    data: &[u8],
+    model: LinChecksum,
+) -> Result<(), LinError>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    check_length(data.len())?;
+    let deadline = response_deadline(serial, timer, data.len());
+    write_checked(serial, data, deadline)?;
+    write_checked(serial, &[lin_checksum(model, pid, data)], deadline)
+}
+
+fn receive_response<const I: u8, TX, RX>(
+    serial: &mut BlockingSerial<'_, I, TX, RX>,
+    timer: &TimerDelay<'_>,
+    pid: u8,
+    buf: &mut [u8],
+    model: LinChecksum,
+) -> Result<(), LinError>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    check_length(buf.len())?;
+    let deadline = Some(response_deadline(serial, timer, buf.len()));
+    for b in buf.iter_mut() {
+        *b = read_byte(serial, deadline)?;
+    }
+    if read_byte(serial, deadline)? != lin_checksum(model, pid, buf) {
+        return Err(LinError::Checksum);
+    }
+    Ok(())
+}
+
+/// Space bytes by one bit time so the break delimiter is long enough.
+fn set_inter_byte_space<const I: u8, TX, RX>(serial: &BlockingSerial<'_, I, TX, RX>, bits: u8)
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    unsafe { serial.reg.tx_delay.modify(|v| v.set_transmit_delay(bits)) };
+}
+
+/// LIN master (commander) node.
+pub struct LinMaster<'a, const I: u8, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    serial: BlockingSerial<'a, I, TX, RX>,
+    timer: &'a TimerDelay<'a>,
+}
+
+impl<'a, const I: u8, TX, RX> LinMaster<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    /// Create a LIN master on `serial`, timing frames with `timer`.
+    pub fn new(serial: BlockingSerial<'a, I, TX, RX>, timer: &'a TimerDelay<'a>) -> Self {
+        set_inter_byte_space(&serial, 1);
+        Self { serial, timer }
+    }
+
+    /// Send the header of frame `id`: break, sync and protected identifier.
+    ///
+    /// Fails with [`LinError::Id`] if `id` is not in 0..=63.
+    pub fn send_header(&mut self, id: u8) -> Result<(), LinError> {
+        let pid = lin_protected_id(id)?;
+        self.serial.send_break();
+        let deadline = Deadline::after_bits(self.timer, self.serial.baud_rate(), LIN_HEADER_BITS);
+        match read_byte(&mut self.serial, Some(deadline)) {
+            Err(LinError::Uart(Error::Break)) => {}
+            Err(e) => return Err(e),
+            Ok(_) => return Err(LinError::Readback),
+        }
+        write_checked(&mut self.serial, &[LIN_SYNC, pid], deadline)
+    }
+
+    /// Send frame `id` with `data` as the response.
+    ///
+    /// Fails with [`LinError::Length`] before sending the header unless
+    /// `data` has 1 to 8 bytes.
+    pub fn write_frame(&mut self, id: u8, data: &[u8], model: LinChecksum) -> Result<(), LinError> {
+        let pid = lin_protected_id(id)?;
+        check_length(data.len())?;
+        self.send_header(id)?;
+        send_response(&mut self.serial, self.timer, pid, data, model)
+    }
+
+    /// Send the header of frame `id` and read the response of a slave into `buf`.
+    ///
+    /// Fails with [`LinError::Length`] before sending the header unless
+    /// `buf` has 1 to 8 bytes.
+    pub fn read_frame(
+        &mut self,
+        id: u8,
+        buf: &mut [u8],
+        model: LinChecksum,
+    ) -> Result<(), LinError> {
+        let pid = lin_protected_id(id)?;
+        check_length(buf.len())?;
+        self.send_header(id)?;
+        receive_response(&mut self.serial, self.timer, pid, buf, model)
+    }
+
+    /// Release the serial.
+    pub fn free(self) -> BlockingSerial<'a, I, TX, RX> {
+        set_inter_byte_space(&self.serial, 0);
+        self.serial
+    }
+}
+
+/// LIN slave (responder) node.
+pub struct LinSlave<'a, const I: u8, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    serial: BlockingSerial<'a, I, TX, RX>,
+    timer: &'a TimerDelay<'a>,
+}
+
+impl<'a, const I: u8, TX, RX> LinSlave<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    /// Create a LIN slave on `serial`, timing frames with `timer`.
+    pub fn new(serial: BlockingSerial<'a, I, TX, RX>, timer: &'a TimerDelay<'a>) -> Self {
+        set_inter_byte_space(&serial, 1);
+        Self { serial, timer }
+    }
+
+    /// Wait up to `timeout` for a header and return its frame ID.
+    ///
+    /// Bytes and line errors before a break are skipped. The break must be
+    /// followed by the sync byte and a protected identifier with valid parity.
+    pub fn wait_header(&mut self, timeout: Milliseconds<u32>) -> Result<u8, LinError> {
+        let ticks = timeout.0 as u64 * self.timer.tick_freq() as u64 / 1000;
+        let idle = Some(Deadline::after(self.timer, ticks));
+        loop {
+            match read_byte(&mut self.serial, idle) {
+                Err(LinError::Uart(Error::Break)) => break,
+                Err(LinError::Timeout) => return Err(LinError::Timeout),
+                _ => {}
+            }
+        }
+        // The break has been received; sync and identifier follow within the header time.
+        let deadline = Some(Deadline::after_bits(
+            self.timer,
+            self.serial.baud_rate(),
+            LIN_HEADER_BITS,
+        ));
+        if read_byte(&mut self.serial, deadline)? != LIN_SYNC {
+            return Err(LinError::Sync);
+        }
+        let pid = read_byte(&mut self.serial, deadline)?;
+        let id = pid & 0x3F;
+        if lin_protected_id(id) != Ok(pid) {
+            return Err(LinError::Parity);
+        }
+        Ok(id)
+    }
+
+    /// Send `data` as the response to the header of frame `id`.
+    ///
+    /// Fails with [`LinError::Id`] or [`LinError::Length`] unless `id` is in
+    /// 0..=63 and `data` has 1 to 8 bytes.
+    pub fn respond(&mut self, id: u8, data: &[u8], model: LinChecksum) -> Result<(), LinError> {
+        let pid = lin_protected_id(id)?;
+        send_response(&mut self.serial, self.timer, pid, data, model)
+    }
+
+    /// Read the response to the header of frame `id` into `buf`.
+    ///
+    /// Fails with [`LinError::Id`] or [`LinError::Length`] unless `id` is in
+    /// 0..=63 and `buf` has 1 to 8 bytes.
+    pub fn read_response(
+        &mut self,
+        id: u8,
+        buf: &mut [u8],
+        model: LinChecksum,
+    ) -> Result<(), LinError> {
+        let pid = lin_protected_id(id)?;
+        receive_response(&mut self.serial, self.timer, pid, buf, model)
+    }
+
+    /// Release the serial.
+    pub fn free(self) -> BlockingSerial<'a, I, TX, RX> {
+        set_inter_byte_space(&self.serial, 0);
+        self.serial
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinChecksum, LinError, lin_checksum, lin_protected_id};
+
+    #[test]
+    fn lin_protected_id_parity() {
+        assert_eq!(lin_protected_id(0x00), Ok(0x80));
+        assert_eq!(lin_protected_id(0x3C), Ok(0x3C));
+        assert_eq!(lin_protected_id(0x3D), Ok(0x7D));
+        assert_eq!(lin_protected_id(0x40), Err(LinError::Id));
+    }
+
+    #[test]
+    fn lin_checksum_models() {
+        let data = [0x55, 0x93, 0xE5];
+        assert_eq!(lin_checksum(LinChecksum::Classic, 0x4A, &data), 0x31);
+        assert_eq!(lin_checksum(LinChecksum::Enhanced, 0x4A, &data), 0xE6);
+        assert_eq!(lin_checksum(LinChecksum::Classic, 0x4A, &[]), 0xFF);
+    }
+}
@@ -0,0 +1,110 @@
+//! Break generation.
+//!
+//! Received breaks are reported as [`Error::Break`](super::Error::Break) by
+//! the read functions.
+
+use super::blocking::BlockingSerial;
+use super::pad::{Receive, Transmit, UartPad};
+use super::register::RegisterBlock;
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::instance::{Uart, UartInterrupt},
+    super::non_blocking::{AsyncSerial, flush_buffered},
+    core::future::poll_fn,
+    core::task::Poll,
+};
+
+#[inline]
+fn set_break(reg: &RegisterBlock, enable: bool) {
+    unsafe { reg.line_control().modify(|v| v.set_break(enable)) };
+}
+
+#[inline]
+fn is_tx_fifo_empty(reg: &RegisterBlock) -> bool {
+    reg.usr.read().is_transmit_fifo_empty()
+}
+
+/// Queue the two zero characters of a break with break control set across
+/// the stop bit of the first one.
+///
+/// Expects an empty transmit FIFO. Interrupts are disabled for at most one
+/// character time so the stop bit can not pass before break control is set.
+fn start_break(reg: &RegisterBlock) {
+    let uart16550 = &reg.uart16550;
+    uart16550.rbr_thr().tx_data(0x00);
+    critical_section::with(|_| {
+        // The first character has moved to the shifter once the FIFO is empty.
+        while !is_tx_fifo_empty(reg) {
+            core::hint::spin_loop();
+        }
+        set_break(reg, true);
+        uart16550.rbr_thr().tx_data(0x00);
+    });
+}
+
+#[cfg(feature = "clic_interrupts")]
+async fn wait_tx_fifo_empty(reg: &RegisterBlock) {
+    poll_fn(|cx| {
+        if is_tx_fifo_empty(reg) {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+impl<'a, const I: u8, TX, RX> BlockingSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    /// Hold the transmit line low (break) or release it.
+    #[inline]
+    pub fn set_break(&mut self, enable: bool) {
+        set_break(self.reg, enable)
+    }
+
+    /// Send a break of 19 bit times followed by a stop bit.
+    ///
+    /// The break length follows the baud rate, no timer is needed. Queued
+    /// characters are sent first.
+    pub fn send_break(&mut self) {
+        while !is_tx_fifo_empty(self.reg) {
+            core::hint::spin_loop();
+        }
+        start_break(self.reg);
+        while !is_tx_fifo_empty(self.reg) {
+            core::hint::spin_loop();
+        }
+        // The second character keeps the line low until its stop bit.
+        set_break(self.reg, false);
+    }
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'a, const I: u8, TX, RX> AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    /// Hold the transmit line low (break) or release it.
+    #[inline]
+    pub fn set_break(&mut self, enable: bool) {
+        set_break(self.reg, enable)
+    }
+
+    /// Send a break of 19 bit times followed by a stop bit.
+    ///
+    /// Buffered characters are sent first.
+    pub async fn send_break(&mut self) {
+        flush_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state()).await;
+        wait_tx_fifo_empty(self.reg).await;
+        start_break(self.reg);
+        wait_tx_fifo_empty(self.reg).await;
+        // The second character keeps the line low until its stop bit.
+        set_break(self.reg, false);
+    }
+}
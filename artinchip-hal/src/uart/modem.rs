@@ -0,0 +1,81 @@
+//! Hardware flow control and modem status.
+
+use super::blocking::BlockingSerial;
+use super::pad::{Receive, Transmit, UartPad};
+use super::register::{ModemStatus, RegisterBlock};
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::instance::{Uart, UartInterrupt},
+    super::non_blocking::AsyncSerial,
+    core::future::poll_fn,
+    core::task::Poll,
+};
+
+/// Enable auto flow control for the RTS and CTS pads bound to the UART.
+///
+/// With an RTS pad, RTS is de-asserted while the receive FIFO is at its
+/// trigger level; with a CTS pad, transmission pauses while CTS is de-asserted.
+pub(crate) fn configure_flow_control<const I: u8, TX, RX>(reg: &RegisterBlock)
+where
+    TX: Transmit<I>,
+    RX: Receive<I>,
+{
+    let rts = RX::REQUEST_TO_SEND;
+    let cts = TX::CLEAR_TO_SEND;
+    unsafe {
+        reg.modem_control().modify(|v| {
+            if rts || cts {
+                v.enable_auto_flow_control().set_rts(rts)
+            } else {
+                v.disable_auto_flow_control()
+            }
+        });
+    }
+}
+
+impl<'a, const I: u8, TX, RX> BlockingSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    /// Read the modem status, clearing its change flags.
+    #[inline]
+    pub fn modem_status(&mut self) -> ModemStatus {
+        self.reg.modem_status().read()
+    }
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'a, const I: u8, TX, RX> AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    /// Read the modem status, clearing its change flags.
+    #[inline]
+    pub fn modem_status(&mut self) -> ModemStatus {
+        self.reg.modem_status().read()
+    }
+
+    /// Wait until a modem status line changes, returning the new status.
+    ///
+    /// Enables the modem status interrupt on first use.
+    pub async fn wait_for_modem_status_change(&mut self) -> ModemStatus {
+        let state = <Uart<I> as UartInterrupt<I>>::state();
+        let uart16550 = &self.reg.uart16550;
+        critical_section::with(|cs| {
+            state.modem_status.borrow(cs).set(None);
+            let ier = uart16550.ier().read();
+            uart16550.ier().write(ier.enable_ms());
+        });
+        poll_fn(|cx| {
+            state.modem_waker.register(cx.waker());
+            match critical_section::with(|cs| state.modem_status.borrow(cs).take()) {
+                Some(status) => Poll::Ready(status),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
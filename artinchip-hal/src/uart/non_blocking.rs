@@ -1,30 +1,54 @@
 //! Async serial communication interface.
 
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
 use core::future::poll_fn;
+use core::sync::atomic::{AtomicBool, Ordering};
 use core::task::Poll;
 
 use critical_section::Mutex;
 use embassy_sync::waitqueue::AtomicWaker;
 use uart16550::{PendingInterrupt, TriggerLevel};
 
-use super::config::{StopBits, UartConfig};
+use super::config::UartConfig;
+use super::error::{ConfigError, Error, check_line_status};
 use super::instance::{Uart, UartInterrupt};
 use super::pad::{Receive, Transmit, UartPad};
-use super::register::RegisterBlock;
+use super::register::{ModemStatus, RegisterBlock};
+use super::setup::{apply_config, enable_clock, wait_transmit_idle};
 use crate::cmu::Cmu;
 use crate::interrupt::clic::typelevel::{self, Interrupt as _};
-use crate::types::RingBuffer;
+use crate::types::SliceRingBuffer;
 
-const RX_BUF_SIZE: usize = 256;
-const TX_BUF_SIZE: usize = 256;
 const TX_BUSY_SPIN_LIMIT: usize = 200_000;
 
+/// Caller provided software FIFOs of an async serial.
+///
+/// Size them for the expected burst length, e.g. a few KiB for a
+/// high-throughput logger or a few bytes for a console.
+pub struct AsyncBuffers {
+    /// Transmit buffer; must not be empty, writes wait for free space in it.
+    pub tx: &'static mut [u8],
+    /// Receive buffer.
+    pub rx: &'static mut [u8],
+}
+
+/// Async state of one UART instance.
+///
+/// One state exists per instance implementing `UartInterrupt`; it is only
+/// linked in if the instance is used asynchronously. Buffers are provided by
+/// the caller when creating the `AsyncSerial`.
 pub struct AsyncState {
     pub rx_waker: AtomicWaker,
     pub tx_waker: AtomicWaker,
-    pub rx_buffer: Mutex<RefCell<RingBuffer<u8, RX_BUF_SIZE>>>,
-    pub tx_buffer: Mutex<RefCell<RingBuffer<u8, TX_BUF_SIZE>>>,
+    pub rx_buffer: Mutex<RefCell<SliceRingBuffer<'static, u8>>>,
+    pub tx_buffer: Mutex<RefCell<SliceRingBuffer<'static, u8>>>,
+    /// First receive line error not yet reported to the reader.
+    pub rx_error: Mutex<Cell<Option<Error>>>,
+    /// Set when the receive line went idle after the last character.
+    pub rx_idle: AtomicBool,
+    pub modem_waker: AtomicWaker,
+    /// Modem status read by the last modem status interrupt.
+    pub modem_status: Mutex<Cell<Option<ModemStatus>>>,
 }
 
 impl AsyncState {
@@ -32,10 +56,25 @@ impl AsyncState {
         Self {
             rx_waker: AtomicWaker::new(),
             tx_waker: AtomicWaker::new(),
-            rx_buffer: Mutex::new(RefCell::new(RingBuffer::new())),
-            tx_buffer: Mutex::new(RefCell::new(RingBuffer::new())),
+            rx_buffer: Mutex::new(RefCell::new(SliceRingBuffer::empty())),
+            tx_buffer: Mutex::new(RefCell::new(SliceRingBuffer::empty())),
+            rx_error: Mutex::new(Cell::new(None)),
+            rx_idle: AtomicBool::new(false),
+            modem_waker: AtomicWaker::new(),
+            modem_status: Mutex::new(Cell::new(None)),
         }
     }
+
+    /// Attach caller provided buffers and reset receive status.
+    fn init(&self, buffers: AsyncBuffers) {
+        critical_section::with(|cs| {
+            self.tx_buffer.borrow_ref_mut(cs).replace(buffers.tx);
+            self.rx_buffer.borrow_ref_mut(cs).replace(buffers.rx);
+            self.rx_error.borrow(cs).set(None);
+            self.modem_status.borrow(cs).set(None);
+        });
+        self.rx_idle.store(false, Ordering::Relaxed);
+    }
 }
 
 impl Default for AsyncState {
@@ -44,18 +83,6 @@ impl Default for AsyncState {
     }
 }
 
-/// Global array holding the state for up to 8 UART instances.
-pub static UART_STATES: [AsyncState; 8] = [
-    AsyncState::new(),
-    AsyncState::new(),
-    AsyncState::new(),
-    AsyncState::new(),
-    AsyncState::new(),
-    AsyncState::new(),
-    AsyncState::new(),
-    AsyncState::new(),
-];
-
 #[inline]
 fn kick_tx_if_idle(reg: &RegisterBlock, state: &AsyncState) {
     // Just peek into the buffer to see if there's any data
@@ -86,7 +113,7 @@ where
     unsafe fn on_interrupt() {
         let reg = unsafe { Uart::<I>::regs_at_index() };
         let uart16550 = &reg.uart16550;
-        let state = &UART_STATES[I as usize];
+        let state = <Uart<I> as UartInterrupt<I>>::state();
 
         let iir = uart16550.iir_fcr().read();
         let pending = match iir.pending_interrupts() {
@@ -99,7 +126,15 @@ where
 
         match pending {
             PendingInterrupt::TransmitterHoldingRegisterEmpty => {
-                let byte = critical_section::with(|cs| state.tx_buffer.borrow_ref_mut(cs).pop());
+                let (byte, was_full) = critical_section::with(|cs| {
+                    let mut tx_buf = state.tx_buffer.borrow_ref_mut(cs);
+                    let was_full = tx_buf.is_full();
+                    (tx_buf.pop(), was_full)
+                });
+                if was_full {
+                    // Writers wait for free space.
+                    state.tx_waker.wake();
+                }
                 if let Some(byte) = byte {
                     let mut spins = 0;
                     while reg.usr.read().is_busy() {
@@ -120,14 +155,31 @@ where
                 }
             }
             PendingInterrupt::ReceivedDataAvailable | PendingInterrupt::ReceivedDataTimeout => {
+                let idle = matches!(pending, PendingInterrupt::ReceivedDataTimeout);
+                // Leave one character in the FIFO on data available, so the
+                // receive timeout interrupt still marks the end of a frame.
+                let keep = if idle { 0 } else { 1 };
                 critical_section::with(|cs| {
                     let mut rx_buf = state.rx_buffer.borrow_ref_mut(cs);
-                    while uart16550.lsr().read().is_data_ready() {
-                        rx_buf.push(uart16550.rbr_thr().rx_data()).ok();
+                    let mut rx_error = state.rx_error.borrow(cs).get();
+                    while reg.rfl.read().rx_level() > keep
+                        && check_line_status(uart16550, &mut rx_error)
+                    {
+                        if rx_buf.push(uart16550.rbr_thr().rx_data()).is_err() {
+                            rx_error.get_or_insert(Error::Overrun);
+                        }
                     }
+                    state.rx_error.borrow(cs).set(rx_error);
                 });
+                state.rx_idle.store(idle, Ordering::Release);
                 state.rx_waker.wake();
             }
+            PendingInterrupt::ModemStatus => {
+                // Reading the modem status clears the interrupt.
+                let status = reg.modem_status().read();
+                critical_section::with(|cs| state.modem_status.borrow(cs).set(Some(status)));
+                state.modem_waker.wake();
+            }
             _ => {}
         }
 
@@ -144,6 +196,8 @@ where
     pub reg: &'a RegisterBlock,
     _tx: TX,
     _rx: RX,
+    // Module clock frequency in Hz.
+    clk: u32,
 }
 
 impl<'a, const I: u8, TX, RX> AsyncSerial<'a, I, TX, RX>
@@ -152,41 +206,24 @@ where
     RX: UartPad<I> + Receive<I>,
     Uart<I>: UartInterrupt<I>,
 {
-    pub fn new(reg: &'a RegisterBlock, tx: TX, rx: RX, config: UartConfig, cmu: &mut Cmu) -> Self {
-        // Enable clocks for the UART instance
-        let fix_mod_clk_rate = 48_000_000;
-        let fix_mod_div = 24;
-        let clk = cmu.register_block();
-        let uart_clk = match I {
-            0 => &clk.clock_uart0,
-            1 => &clk.clock_uart1,
-            2 => &clk.clock_uart2,
-            3 => &clk.clock_uart3,
-            4 => &clk.clock_uart4,
-            5 => &clk.clock_uart5,
-            6 => &clk.clock_uart6,
-            7 => &clk.clock_uart7,
-            _ => panic!("Invalid UART index"),
-        };
-        unsafe {
-            // Initialize module clock.
-            uart_clk.modify(|v| v.set_module_clk_div(fix_mod_div).enable_module_clk());
-            uart_clk.modify(|v| v.enable_bus_clk());
-            uart_clk.modify(|v| v.enable_module_reset());
-            riscv::asm::delay(500);
-            uart_clk.modify(|v| v.disable_module_reset());
-        }
-
-        // Parse configuration
-        let baud_rate = config.baud_rate.0;
-        let data_bits = config.data_bits;
-        let stop_bits = config.stop_bits;
-        let parity = config.parity;
-
-        // Halt uart for configuration
-        unsafe {
-            reg.halt.modify(|v| v.set_halt_change_config_at_busy(true));
-        }
+    /// Create a new async serial using `buffers` as software FIFOs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transmit buffer is empty, or if `config` can not be
+    /// applied, e.g. the baud rate is out of tolerance. Create the serial with
+    /// a known good configuration such as [`UartConfig::default`] and use
+    /// [`Self::set_config`] to handle such errors.
+    pub fn new(
+        reg: &'a RegisterBlock,
+        tx: TX,
+        rx: RX,
+        buffers: AsyncBuffers,
+        config: UartConfig,
+        cmu: &mut Cmu,
+    ) -> Self {
+        assert!(!buffers.tx.is_empty(), "UART transmit buffer is empty");
+        let clk = enable_clock::<I>(cmu);
 
         // Disable all interrupts
         let uart16550 = &reg.uart16550;
@@ -199,100 +236,328 @@ where
                 .disable_thre(),
         );
 
-        // Write baud rate divisor
-        let uart_divisor = fix_mod_clk_rate / (16 * baud_rate);
-        uart16550.write_divisor(uart_divisor as u16);
-
-        // Update HALT register to apply configuration
-        unsafe {
-            reg.halt.modify(|v| v.set_halt_change_update(true));
-        }
-
-        // Configure line control register
-        let lcr = uart16550.lcr().read();
-        uart16550.lcr().write(
-            lcr.set_char_len(data_bits.to_char_len())
-                .set_one_stop_bit(stop_bits == StopBits::One)
-                .set_parity(parity.to_parity()),
-        );
+        apply_config::<I, TX, RX>(reg, &config, clk).expect("Invalid UART configuration");
 
         // Enable FIFO and set trigger levels
         uart16550.iir_fcr().write(TriggerLevel::_14.and_reset());
+
+        <Uart<I> as UartInterrupt<I>>::state().init(buffers);
+
+        // Receive through the interrupt handler
+        let ier = uart16550.ier().read();
+        uart16550.ier().write(ier.enable_rda());
+
         Self {
             reg,
             _tx: tx,
             _rx: rx,
+            clk,
         }
     }
+
+    /// Change baud rate, line settings and flow control at runtime.
+    ///
+    /// Waits for buffered characters to be sent first. On error the previous
+    /// baud rate and line settings are restored and flow control is unchanged.
+    pub async fn set_config(&mut self, config: UartConfig) -> Result<(), ConfigError> {
+        flush_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state()).await;
+        wait_transmit_idle(self.reg);
+        apply_config::<I, TX, RX>(self.reg, &config, self.clk)
+    }
+
+    /// Read until the receive line goes idle or `buf` is full.
+    ///
+    /// Uses the receive timeout interrupt, raised after about four character
+    /// times without new data, to detect the end of a frame.
+    #[inline]
+    pub async fn read_until_idle(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        read_until_idle(<Uart<I> as UartInterrupt<I>>::state(), buf).await
+    }
+
+    /// Split into transmit and receive halves that can be used by separate tasks.
+    pub fn split(self) -> (AsyncTransmitHalf<'a, I, TX>, AsyncReceiveHalf<'a, I, RX>) {
+        (
+            AsyncTransmitHalf {
+                reg: self.reg,
+                _pad: self._tx,
+            },
+            AsyncReceiveHalf {
+                _reg: self.reg,
+                _pad: self._rx,
+            },
+        )
+    }
 }
 
-impl<'a, const I: u8, TX, RX> embedded_io_async::ErrorType for AsyncSerial<'a, I, TX, RX>
+/// Transmit half of the async serial interface.
+pub struct AsyncTransmitHalf<'a, const I: u8, TX>
 where
     TX: UartPad<I> + Transmit<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    reg: &'a RegisterBlock,
+    _pad: TX,
+}
+
+/// Receive half of the async serial interface.
+pub struct AsyncReceiveHalf<'a, const I: u8, RX>
+where
     RX: UartPad<I> + Receive<I>,
     Uart<I>: UartInterrupt<I>,
 {
-    type Error = core::convert::Infallible;
+    _reg: &'a RegisterBlock,
+    _pad: RX,
 }
 
-impl<'a, const I: u8, TX, RX> embedded_io_async::Write for AsyncSerial<'a, I, TX, RX>
+impl<'a, const I: u8, RX> AsyncReceiveHalf<'a, I, RX>
 where
-    TX: UartPad<I> + Transmit<I>,
     RX: UartPad<I> + Receive<I>,
     Uart<I>: UartInterrupt<I>,
 {
-    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
-        let state = &UART_STATES[I as usize];
+    /// Read until the receive line goes idle or `buf` is full.
+    #[inline]
+    pub async fn read_until_idle(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        read_until_idle(<Uart<I> as UartInterrupt<I>>::state(), buf).await
+    }
+}
 
+/// Queue bytes of `buf`, waiting until at least one fits into the transmit
+/// buffer.
+async fn write_buffered(reg: &RegisterBlock, state: &AsyncState, buf: &[u8]) -> usize {
+    if buf.is_empty() {
+        return 0;
+    }
+    poll_fn(|cx| {
+        state.tx_waker.register(cx.waker());
         let written = critical_section::with(|cs| {
             let mut tx_buf = state.tx_buffer.borrow_ref_mut(cs);
-            let mut w = 0;
-            for &b in buf {
-                if tx_buf.push(b).is_ok() {
-                    w += 1;
-                } else {
-                    break;
-                }
-            }
-            w
+            buf.iter().take_while(|&&b| tx_buf.push(b).is_ok()).count()
         });
 
         // Kick once to avoid relying solely on THRE edge, which may cause await to hang occasionally.
-        kick_tx_if_idle(self.reg, state);
+        kick_tx_if_idle(reg, state);
 
-        Ok(written)
-    }
+        if written == 0 {
+            // Woken by the interrupt handler once a byte leaves the full buffer.
+            Poll::Pending
+        } else {
+            Poll::Ready(written)
+        }
+    })
+    .await
+}
 
-    async fn flush(&mut self) -> Result<(), Self::Error> {
-        let state = &UART_STATES[I as usize];
-        let reg = self.reg;
+pub(super) async fn flush_buffered(reg: &RegisterBlock, state: &AsyncState) {
+    // Kick once to ensure data transfer starts even if interrupt chain is not established.
+    kick_tx_if_idle(reg, state);
+
+    // Async wait: let the interrupt handler empty the RAM buffer (tx_buffer)
+    poll_fn(|cx| {
+        state.tx_waker.register(cx.waker());
 
-        // Kick once to ensure data transfer starts even if interrupt chain is not established.
-        kick_tx_if_idle(self.reg, state);
+        // If the interrupt does not continue to trigger, but the hardware FIFO is empty, actively kick again.
+        kick_tx_if_idle(reg, state);
+
+        let tx_empty = critical_section::with(|cs| state.tx_buffer.borrow_ref(cs).is_empty());
+        if tx_empty {
+            Poll::Ready(())
+        } else {
+            // Wake the task to ensure it gets polled again, in case the interrupt was missed.
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+
+    // Sync wait: RAM is empty, wait for the underlying hardware to physically send out the last bit of data
+    while reg.usr.read().is_busy() {
+        core::hint::spin_loop();
+    }
+}
 
-        // Async wait: let the interrupt handler empty the RAM buffer (tx_buffer)
-        poll_fn(|cx| {
-            state.tx_waker.register(cx.waker());
+/// Move buffered received bytes into `buf`, returning the count.
+#[inline]
+fn pop_received(
+    state: &AsyncState,
+    cs: critical_section::CriticalSection,
+    buf: &mut [u8],
+) -> usize {
+    let mut rx_buf = state.rx_buffer.borrow_ref_mut(cs);
+    let mut count = 0;
+    while count < buf.len() {
+        match rx_buf.pop() {
+            Some(b) => {
+                buf[count] = b;
+                count += 1;
+            }
+            None => break,
+        }
+    }
+    count
+}
 
-            // If the interrupt does not continue to trigger, but the hardware FIFO is empty, actively kick again.
-            kick_tx_if_idle(self.reg, state);
+async fn read_buffered(state: &AsyncState, buf: &mut [u8]) -> Result<usize, Error> {
+    if buf.is_empty() {
+        return Ok(0);
+    }
+    poll_fn(|cx| {
+        state.rx_waker.register(cx.waker());
+        critical_section::with(|cs| {
+            // Bytes received before an error are delivered first.
+            let count = pop_received(state, cs, buf);
+            if count > 0 {
+                return Poll::Ready(Ok(count));
+            }
+            match state.rx_error.borrow(cs).take() {
+                Some(e) => Poll::Ready(Err(e)),
+                None => Poll::Pending,
+            }
+        })
+    })
+    .await
+}
 
-            let tx_empty = critical_section::with(|cs| state.tx_buffer.borrow_ref(cs).is_empty());
-            if tx_empty {
-                Poll::Ready(Ok::<(), Self::Error>(()))
+async fn read_until_idle(state: &AsyncState, buf: &mut [u8]) -> Result<usize, Error> {
+    if buf.is_empty() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    poll_fn(|cx| {
+        state.rx_waker.register(cx.waker());
+        critical_section::with(|cs| {
+            count += pop_received(state, cs, &mut buf[count..]);
+            let rx_error = state.rx_error.borrow(cs);
+            if let Some(e) = rx_error.get() {
+                // Bytes received before an error are delivered first; the
+                // error is kept for the next read.
+                if count > 0 {
+                    return Poll::Ready(Ok(count));
+                }
+                rx_error.set(None);
+                return Poll::Ready(Err(e));
+            }
+            let drained = state.rx_buffer.borrow_ref(cs).is_empty();
+            if count == buf.len()
+                || (count > 0 && drained && state.rx_idle.swap(false, Ordering::Acquire))
+            {
+                Poll::Ready(Ok(count))
             } else {
-                // Wake the task to ensure it gets polled again, in case the interrupt was missed.
-                cx.waker().wake_by_ref();
                 Poll::Pending
             }
         })
-        .await?;
+    })
+    .await
+}
 
-        // Sync wait: RAM is empty, wait for the underlying hardware to physically send out the last bit of data
-        while reg.usr.read().is_busy() {
-            core::hint::spin_loop();
-        }
+#[inline]
+fn read_ready(state: &AsyncState) -> bool {
+    critical_section::with(|cs| {
+        !state.rx_buffer.borrow_ref(cs).is_empty() || state.rx_error.borrow(cs).get().is_some()
+    })
+}
 
+impl<'a, const I: u8, TX, RX> embedded_io_async::ErrorType for AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    type Error = Error;
+}
+
+impl<'a, const I: u8, TX> embedded_io_async::ErrorType for AsyncTransmitHalf<'a, I, TX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    type Error = core::convert::Infallible;
+}
+
+impl<'a, const I: u8, RX> embedded_io_async::ErrorType for AsyncReceiveHalf<'a, I, RX>
+where
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    type Error = Error;
+}
+
+impl<'a, const I: u8, TX, RX> embedded_io_async::Write for AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    #[inline]
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(write_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state(), buf).await)
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        flush_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state()).await;
         Ok(())
     }
 }
+
+impl<'a, const I: u8, TX> embedded_io_async::Write for AsyncTransmitHalf<'a, I, TX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    #[inline]
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(write_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state(), buf).await)
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        flush_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state()).await;
+        Ok(())
+    }
+}
+
+impl<'a, const I: u8, TX, RX> embedded_io_async::Read for AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    #[inline]
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_buffered(<Uart<I> as UartInterrupt<I>>::state(), buf).await
+    }
+}
+
+impl<'a, const I: u8, RX> embedded_io_async::Read for AsyncReceiveHalf<'a, I, RX>
+where
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    #[inline]
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_buffered(<Uart<I> as UartInterrupt<I>>::state(), buf).await
+    }
+}
+
+impl<'a, const I: u8, TX, RX> embedded_io_async::ReadReady for AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + Transmit<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(read_ready(<Uart<I> as UartInterrupt<I>>::state()))
+    }
+}
+
+impl<'a, const I: u8, RX> embedded_io_async::ReadReady for AsyncReceiveHalf<'a, I, RX>
+where
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(read_ready(<Uart<I> as UartInterrupt<I>>::state()))
+    }
+}
@@ -1,5 +1,74 @@
 //! UART pad.
 
 pub trait UartPad<const I: u8> {}
-pub trait Transmit<const I: u8>: UartPad<I> {}
-pub trait Receive<const I: u8>: UartPad<I> {}
+pub trait Transmit<const I: u8>: UartPad<I> {
+    /// Whether a CTS pad gates this transmit pad.
+    const CLEAR_TO_SEND: bool = false;
+}
+pub trait Receive<const I: u8>: UartPad<I> {
+    /// Whether an RTS pad signals this receive pad is ready.
+    const REQUEST_TO_SEND: bool = false;
+}
+/// RS-485 driver enable (DE) pad, shared with the RTS output.
+pub trait DriverEnable<const I: u8>: UartPad<I> {}
+/// Request to send (RTS) output pad.
+pub trait RequestToSend<const I: u8>: UartPad<I> {}
+/// Clear to send (CTS) input pad.
+pub trait ClearToSend<const I: u8>: UartPad<I> {}
+/// Transmit pad bound with an RS-485 driver enable pad.
+pub trait WithDe<const I: u8>: Transmit<I> {}
+
+/// Transmit pad paired with an RS-485 driver enable pad.
+impl<const I: u8, TX, DE> UartPad<I> for (TX, DE)
+where
+    TX: Transmit<I>,
+    DE: DriverEnable<I>,
+{
+}
+impl<const I: u8, TX, DE> Transmit<I> for (TX, DE)
+where
+    TX: Transmit<I>,
+    DE: DriverEnable<I>,
+{
+}
+impl<const I: u8, TX, DE> WithDe<I> for (TX, DE)
+where
+    TX: Transmit<I>,
+    DE: DriverEnable<I>,
+{
+}
+
+/// Transmit pad paused by hardware while the CTS input is de-asserted.
+pub struct WithCts<TX, CTS>(pub TX, pub CTS);
+
+impl<const I: u8, TX, CTS> UartPad<I> for WithCts<TX, CTS>
+where
+    TX: Transmit<I>,
+    CTS: ClearToSend<I>,
+{
+}
+impl<const I: u8, TX, CTS> Transmit<I> for WithCts<TX, CTS>
+where
+    TX: Transmit<I>,
+    CTS: ClearToSend<I>,
+{
+    const CLEAR_TO_SEND: bool = true;
+}
+
+/// Receive pad with an RTS output de-asserted by hardware when the receive
+/// FIFO reaches its trigger level.
+pub struct WithRts<RX, RTS>(pub RX, pub RTS);
+
+impl<const I: u8, RX, RTS> UartPad<I> for WithRts<RX, RTS>
+where
+    RX: Receive<I>,
+    RTS: RequestToSend<I>,
+{
+}
+impl<const I: u8, RX, RTS> Receive<I> for WithRts<RX, RTS>
+where
+    RX: Receive<I>,
+    RTS: RequestToSend<I>,
+{
+    const REQUEST_TO_SEND: bool = true;
+}
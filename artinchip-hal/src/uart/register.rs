@@ -63,6 +63,267 @@ pub struct RegisterBlock {
     pub version: RO<Version>,
 }
 
+impl RegisterBlock {
+    /// UART line control register (`UART_LCR`).
+    ///
+    /// Typed view of the break control field of the line control register in
+    /// [`Self::uart16550`].
+    #[doc(alias = "UART_LCR")]
+    #[inline]
+    pub fn line_control(&self) -> &RW<LineControl> {
+        unsafe { &*((self as *const Self as *const u8).add(0x0C) as *const RW<LineControl>) }
+    }
+    /// UART modem control register (`UART_MCR`).
+    ///
+    /// Typed view of the modem control register in [`Self::uart16550`] with
+    /// the ArtInChip specific function and flow control fields.
+    #[doc(alias = "UART_MCR")]
+    #[inline]
+    pub fn modem_control(&self) -> &RW<ModemControl> {
+        unsafe { &*((self as *const Self as *const u8).add(0x10) as *const RW<ModemControl>) }
+    }
+    /// UART modem status register (`UART_MSR`).
+    ///
+    /// Typed view of the modem status register in [`Self::uart16550`]. Reading
+    /// it clears the change flags.
+    #[doc(alias = "UART_MSR")]
+    #[inline]
+    pub fn modem_status(&self) -> &RO<ModemStatus> {
+        unsafe { &*((self as *const Self as *const u8).add(0x18) as *const RO<ModemStatus>) }
+    }
+}
+
+/// UART line control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct LineControl(u32);
+
+impl LineControl {
+    const BC: u32 = 0x1 << 6;
+    const SP: u32 = 0x1 << 5;
+    const EPS: u32 = 0x1 << 4;
+    const PEN: u32 = 0x1 << 3;
+    const DLS: u32 = 0x3;
+
+    /// Set break control (`BC`).
+    ///
+    /// Forces the serial output low while set.
+    #[doc(alias = "BC")]
+    #[inline]
+    pub const fn set_break(self, enable: bool) -> Self {
+        if enable {
+            Self(self.0 | Self::BC)
+        } else {
+            Self(self.0 & !Self::BC)
+        }
+    }
+    /// Check if break control is set.
+    #[inline]
+    pub const fn is_break_set(self) -> bool {
+        (self.0 & Self::BC) != 0
+    }
+    /// Select 9-bit frames with `ninth` as the ninth bit (`DLS`, `PEN`, `EPS`, `SP`).
+    ///
+    /// Sends 8 data bits followed by a stick parity bit carrying `ninth`.
+    #[doc(alias = "SP")]
+    #[inline]
+    pub const fn set_nine_bit_frame(self, ninth: bool) -> Self {
+        let v = self.0 | Self::DLS | Self::PEN | Self::SP;
+        if ninth {
+            Self(v & !Self::EPS)
+        } else {
+            Self(v | Self::EPS)
+        }
+    }
+    /// Disable stick parity (`SP`), leaving 9-bit frames.
+    #[inline]
+    pub const fn disable_stick_parity(self) -> Self {
+        Self(self.0 & !Self::SP)
+    }
+    /// Get the ninth bit of 9-bit frames, or `None` if not selected.
+    #[inline]
+    pub const fn nine_bit_frame(self) -> Option<bool> {
+        let mask = Self::DLS | Self::PEN | Self::SP;
+        if (self.0 & mask) == mask {
+            Some((self.0 & Self::EPS) == 0)
+        } else {
+            None
+        }
+    }
+}
+
+/// UART function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum UartFunction {
+    /// Standard UART.
+    Uart = 0,
+    /// IrDA SIR.
+    IrDA = 1,
+    /// RS-485 half-duplex.
+    Rs485 = 2,
+}
+
+/// UART modem control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ModemControl(u32);
+
+impl ModemControl {
+    const UART_FUNCTION: u32 = 0x3 << 6;
+    const AFCE: u32 = 0x1 << 5;
+    const LOOP: u32 = 0x1 << 4;
+    const RTS: u32 = 0x1 << 1;
+    const DTR: u32 = 0x1;
+
+    /// Set the UART function (`UART_FUNCTION`).
+    #[doc(alias = "UART_FUNCTION")]
+    #[inline]
+    pub const fn set_function(self, function: UartFunction) -> Self {
+        Self((self.0 & !Self::UART_FUNCTION) | ((function as u32) << 6))
+    }
+    /// Get the UART function.
+    #[inline]
+    pub const fn function(self) -> UartFunction {
+        match (self.0 & Self::UART_FUNCTION) >> 6 {
+            0 => UartFunction::Uart,
+            1 => UartFunction::IrDA,
+            2 => UartFunction::Rs485,
+            _ => unreachable!(),
+        }
+    }
+    /// Enable auto flow control (`AFCE`).
+    #[doc(alias = "AFCE")]
+    #[inline]
+    pub const fn enable_auto_flow_control(self) -> Self {
+        Self(self.0 | Self::AFCE)
+    }
+    /// Disable auto flow control.
+    #[inline]
+    pub const fn disable_auto_flow_control(self) -> Self {
+        Self(self.0 & !Self::AFCE)
+    }
+    /// Check if auto flow control is enabled.
+    #[inline]
+    pub const fn is_auto_flow_control_enabled(self) -> bool {
+        (self.0 & Self::AFCE) != 0
+    }
+    /// Enable loopback mode (`LOOP`).
+    #[doc(alias = "LOOP")]
+    #[inline]
+    pub const fn enable_loopback(self) -> Self {
+        Self(self.0 | Self::LOOP)
+    }
+    /// Disable loopback mode.
+    #[inline]
+    pub const fn disable_loopback(self) -> Self {
+        Self(self.0 & !Self::LOOP)
+    }
+    /// Check if loopback mode is enabled.
+    #[inline]
+    pub const fn is_loopback_enabled(self) -> bool {
+        (self.0 & Self::LOOP) != 0
+    }
+    /// Set request to send (`RTS`).
+    ///
+    /// Drives the RTS output low (active) when set; in RS-485 software control
+    /// mode this drives the DE output.
+    #[doc(alias = "RTS")]
+    #[inline]
+    pub const fn set_rts(self, active: bool) -> Self {
+        if active {
+            Self(self.0 | Self::RTS)
+        } else {
+            Self(self.0 & !Self::RTS)
+        }
+    }
+    /// Check if request to send is set.
+    #[inline]
+    pub const fn is_rts_set(self) -> bool {
+        (self.0 & Self::RTS) != 0
+    }
+    /// Set data terminal ready (`DTR`).
+    #[doc(alias = "DTR")]
+    #[inline]
+    pub const fn set_dtr(self, active: bool) -> Self {
+        if active {
+            Self(self.0 | Self::DTR)
+        } else {
+            Self(self.0 & !Self::DTR)
+        }
+    }
+    /// Check if data terminal ready is set.
+    #[inline]
+    pub const fn is_dtr_set(self) -> bool {
+        (self.0 & Self::DTR) != 0
+    }
+}
+
+/// UART modem status register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ModemStatus(u32);
+
+impl ModemStatus {
+    const DCD: u32 = 0x1 << 7;
+    const RI: u32 = 0x1 << 6;
+    const DSR: u32 = 0x1 << 5;
+    const CTS: u32 = 0x1 << 4;
+    const DDCD: u32 = 0x1 << 3;
+    const TERI: u32 = 0x1 << 2;
+    const DDSR: u32 = 0x1 << 1;
+    const DCTS: u32 = 0x1;
+
+    /// Check if data carrier detect is asserted (`DCD`).
+    #[doc(alias = "DCD")]
+    #[inline]
+    pub const fn is_data_carrier_detect(self) -> bool {
+        (self.0 & Self::DCD) != 0
+    }
+    /// Check if ring indicator is asserted (`RI`).
+    #[doc(alias = "RI")]
+    #[inline]
+    pub const fn is_ring_indicator(self) -> bool {
+        (self.0 & Self::RI) != 0
+    }
+    /// Check if data set ready is asserted (`DSR`).
+    #[doc(alias = "DSR")]
+    #[inline]
+    pub const fn is_data_set_ready(self) -> bool {
+        (self.0 & Self::DSR) != 0
+    }
+    /// Check if clear to send is asserted (`CTS`).
+    #[doc(alias = "CTS")]
+    #[inline]
+    pub const fn is_clear_to_send(self) -> bool {
+        (self.0 & Self::CTS) != 0
+    }
+    /// Check if data carrier detect changed since the last read (`DDCD`).
+    #[doc(alias = "DDCD")]
+    #[inline]
+    pub const fn is_data_carrier_detect_changed(self) -> bool {
+        (self.0 & Self::DDCD) != 0
+    }
+    /// Check if ring indicator was de-asserted since the last read (`TERI`).
+    #[doc(alias = "TERI")]
+    #[inline]
+    pub const fn is_ring_indicator_trailing_edge(self) -> bool {
+        (self.0 & Self::TERI) != 0
+    }
+    /// Check if data set ready changed since the last read (`DDSR`).
+    #[doc(alias = "DDSR")]
+    #[inline]
+    pub const fn is_data_set_ready_changed(self) -> bool {
+        (self.0 & Self::DDSR) != 0
+    }
+    /// Check if clear to send changed since the last read (`DCTS`).
+    #[doc(alias = "DCTS")]
+    #[inline]
+    pub const fn is_clear_to_send_changed(self) -> bool {
+        (self.0 & Self::DCTS) != 0
+    }
+}
+
 /// UART scratch register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -260,6 +521,13 @@ impl HaltTx {
             Self(self.0 & !Self::HALT_CHANGE_UPDATE)
         }
     }
+    /// Check if a halt change update is pending.
+    ///
+    /// Cleared by hardware once the new configuration is applied.
+    #[inline]
+    pub const fn is_halt_change_update_pending(self) -> bool {
+        (self.0 & Self::HALT_CHANGE_UPDATE) != 0
+    }
     /// Set halt change config at busy (`HALT_CHCFG_AT_BUSY`).
     #[inline]
     pub const fn set_halt_change_config_at_busy(self, enable: bool) -> Self {
@@ -561,6 +829,15 @@ impl Rs485BusIdleCheck {
             _ => unreachable!(),
         }
     }
+    /// Set the bus idle time (`ADJ_TIME`).
+    ///
+    /// The bus idle time, where each unit represents 8 × 16 × Tclk.
+    #[doc(alias = "ADJ_TIME")]
+    #[inline]
+    pub const fn set_bus_idle_time(self, time: u8) -> Self {
+        assert!(time < 64, "Bus idle time out of range (expected 0..=63)");
+        Self((self.0 & !Self::ADJ_TIME) | (Self::ADJ_TIME & (time as u32)))
+    }
     /// Get the bus idle time.
     ///
     /// The bus idle time, where each unit represents 8 × 16 × Tclk.
@@ -634,9 +911,10 @@ impl Version {
 mod tests {
     use super::{
         DebugDlh, DebugDll, DebugRegister, DmaHandshakeConfig, DmaHandshakeMode, HaltTx,
-        ReceiveFifoLevel, RegisterBlock, Rs485AddressMatch, Rs485BusIdleCheck, Rs485BusStatus,
-        Rs485Control, Rs485ControlMode, Rs485DeTime, Rs485SlaveMode, TransmitDelay,
-        TransmitFifoLevel, UartScratch, UartStatus, Version,
+        LineControl, ModemControl, ModemStatus, ReceiveFifoLevel, RegisterBlock, Rs485AddressMatch,
+        Rs485BusIdleCheck, Rs485BusStatus, Rs485Control, Rs485ControlMode, Rs485DeTime,
+        Rs485SlaveMode, TransmitDelay, TransmitFifoLevel, UartFunction, UartScratch, UartStatus,
+        Version,
     };
     use crate::test_should_panic;
     use core::mem::offset_of;
@@ -722,8 +1000,10 @@ mod tests {
         assert_eq!(val.0, 0x0000_0000);
 
         val = val.set_halt_change_update(true);
+        assert!(val.is_halt_change_update_pending());
         assert_eq!(val.0, 0x0000_0004);
         val = val.set_halt_change_update(false);
+        assert!(!val.is_halt_change_update_pending());
         assert_eq!(val.0, 0x0000_0000);
 
         val = val.set_halt_change_config_at_busy(true);
@@ -851,6 +1131,96 @@ mod tests {
 
         val = Rs485BusIdleCheck(0x0000_003F);
         assert_eq!(val.bus_idle_time(), 0x3F);
+
+        val = Rs485BusIdleCheck(0).set_bus_idle_time(0x3F);
+        assert_eq!(val.bus_idle_time(), 0x3F);
+        assert_eq!(val.0, 0x0000_003F);
+    }
+
+    test_should_panic!((
+        test_rs485_set_bus_idle_time_panic,
+        Rs485BusIdleCheck(0).set_bus_idle_time(64),
+        "Bus idle time out of range (expected 0..=63)"
+    ));
+
+    #[test]
+    fn struct_line_control_functions() {
+        let mut val = LineControl(0x03).set_break(true);
+        assert!(val.is_break_set());
+        assert_eq!(val.0, 0x0000_0043);
+        val = val.set_break(false);
+        assert!(!val.is_break_set());
+        assert_eq!(val.0, 0x0000_0003);
+
+        val = LineControl(0x0).set_nine_bit_frame(true);
+        assert_eq!(val.nine_bit_frame(), Some(true));
+        assert_eq!(val.0, 0x0000_002B);
+        val = val.set_nine_bit_frame(false);
+        assert_eq!(val.nine_bit_frame(), Some(false));
+        assert_eq!(val.0, 0x0000_003B);
+        assert_eq!(LineControl(0x1B).nine_bit_frame(), None);
+        val = val.disable_stick_parity();
+        assert_eq!(val.nine_bit_frame(), None);
+        assert_eq!(val.0, 0x0000_001B);
+    }
+
+    #[test]
+    fn struct_modem_status_functions() {
+        let val = ModemStatus(0xF0);
+        assert!(val.is_data_carrier_detect());
+        assert!(val.is_ring_indicator());
+        assert!(val.is_data_set_ready());
+        assert!(val.is_clear_to_send());
+        assert!(!val.is_data_carrier_detect_changed());
+        assert!(!val.is_ring_indicator_trailing_edge());
+        assert!(!val.is_data_set_ready_changed());
+        assert!(!val.is_clear_to_send_changed());
+
+        let val = ModemStatus(0x0F);
+        assert!(!val.is_data_carrier_detect());
+        assert!(!val.is_ring_indicator());
+        assert!(!val.is_data_set_ready());
+        assert!(!val.is_clear_to_send());
+        assert!(val.is_data_carrier_detect_changed());
+        assert!(val.is_ring_indicator_trailing_edge());
+        assert!(val.is_data_set_ready_changed());
+        assert!(val.is_clear_to_send_changed());
+    }
+
+    #[test]
+    fn struct_modem_control_functions() {
+        let mut val = ModemControl(0);
+        val = val.set_function(UartFunction::Rs485);
+        assert_eq!(val.function(), UartFunction::Rs485);
+        assert_eq!(val.0, 0x0000_0080);
+
+        val = val.set_function(UartFunction::IrDA);
+        assert_eq!(val.function(), UartFunction::IrDA);
+        assert_eq!(val.0, 0x0000_0040);
+
+        val = ModemControl(0).enable_auto_flow_control();
+        assert!(val.is_auto_flow_control_enabled());
+        assert_eq!(val.0, 0x0000_0020);
+        val = val.disable_auto_flow_control();
+        assert!(!val.is_auto_flow_control_enabled());
+
+        val = ModemControl(0).enable_loopback();
+        assert!(val.is_loopback_enabled());
+        assert_eq!(val.0, 0x0000_0010);
+        val = val.disable_loopback();
+        assert!(!val.is_loopback_enabled());
+
+        val = ModemControl(0).set_rts(true);
+        assert!(val.is_rts_set());
+        assert_eq!(val.0, 0x0000_0002);
+        val = val.set_rts(false);
+        assert!(!val.is_rts_set());
+
+        val = ModemControl(0).set_dtr(true);
+        assert!(val.is_dtr_set());
+        assert_eq!(val.0, 0x0000_0001);
+        val = val.set_dtr(false);
+        assert!(!val.is_dtr_set());
     }
 
     #[test]
@@ -0,0 +1,249 @@
+//! RS-485 half-duplex mode.
+
+use super::blocking::BlockingSerial;
+use super::config::Rs485Config;
+use super::error::ConfigError;
+use super::pad::{Receive, UartPad, WithDe};
+use super::register::{
+    RegisterBlock, Rs485BusStatus, Rs485ControlMode, Rs485SlaveMode, UartFunction,
+};
+use super::setup::{update_halted, wait_transmit_idle};
+#[cfg(feature = "clic_interrupts")]
+use {
+    super::instance::{Uart, UartInterrupt},
+    super::non_blocking::{AsyncSerial, flush_buffered},
+};
+
+/// Apply the RS-485 configuration and switch to the RS-485 function.
+fn enable_rs485(reg: &RegisterBlock, config: Rs485Config) -> Result<(), ConfigError> {
+    if config.nine_bit || config.address.is_some() {
+        update_halted(reg, || unsafe {
+            reg.line_control().modify(|v| v.set_nine_bit_frame(false))
+        })?;
+    }
+    unsafe {
+        reg.rs485_de.modify(|v| {
+            v.set_de_assert_time(config.de_assert_time)
+                .set_de_deassert_time(config.de_deassert_time)
+        });
+        match config.address {
+            Some(address) => {
+                reg.rs485_addr_match
+                    .modify(|v| v.set_match_address(address as u32));
+                reg.rs485_ctl.modify(|v| {
+                    v.set_rs485_control_mode(Rs485ControlMode::Hardware)
+                        .set_rs485_slave_mode(Rs485SlaveMode::AAD)
+                        .disable_receive_all_before_addr()
+                        .disable_receive_all_after_addr()
+                        .clear_rs485_address_matched()
+                        .clear_rs485_address_detected()
+                });
+            }
+            None => {
+                reg.rs485_ctl.modify(|v| {
+                    v.set_rs485_control_mode(Rs485ControlMode::Hardware)
+                        .set_rs485_slave_mode(Rs485SlaveMode::NMM)
+                        .enable_receive_all_before_addr()
+                        .enable_receive_all_after_addr()
+                });
+            }
+        }
+        match config.bus_idle_time {
+            Some(time) => reg
+                .rs485_bus_idle_check
+                .modify(|v| v.set_bus_idle_time(time).enable_bus_idle_check()),
+            None => reg
+                .rs485_bus_idle_check
+                .modify(|v| v.disable_bus_idle_check()),
+        }
+        reg.modem_control()
+            .modify(|v| v.set_function(UartFunction::Rs485));
+    }
+    Ok(())
+}
+
+/// Switch back to the full-duplex UART function.
+fn disable_rs485(reg: &RegisterBlock) {
+    unsafe {
+        reg.rs485_bus_idle_check
+            .modify(|v| v.disable_bus_idle_check());
+        reg.modem_control()
+            .modify(|v| v.set_function(UartFunction::Uart));
+    }
+}
+
+/// Check that RS-485 was enabled with 9-bit frames.
+#[inline]
+fn check_nine_bit(reg: &RegisterBlock) -> Result<(), ConfigError> {
+    match reg.line_control().read().nine_bit_frame() {
+        Some(_) => Ok(()),
+        None => Err(ConfigError::NotNineBit),
+    }
+}
+
+/// Send `address` as a 9-bit address frame; the transmit FIFO must be empty.
+///
+/// Returns once the address frame is in the shift register; the frames
+/// queued after it are sent as data frames.
+fn send_address(reg: &RegisterBlock, address: u8) -> Result<(), ConfigError> {
+    update_halted(reg, || unsafe {
+        reg.line_control().modify(|v| v.set_nine_bit_frame(true))
+    })?;
+    reg.uart16550.rbr_thr().tx_data(address);
+    wait_transmit_idle(reg);
+    update_halted(reg, || unsafe {
+        reg.line_control().modify(|v| v.set_nine_bit_frame(false))
+    })
+}
+
+#[inline]
+fn is_bus_idle(reg: &RegisterBlock) -> bool {
+    reg.rs485_bus_idle_check.read().bus_status() == Rs485BusStatus::Idle
+}
+
+#[inline]
+fn take_address_matched(reg: &RegisterBlock) -> bool {
+    let ctl = reg.rs485_ctl.read();
+    if ctl.is_rs485_address_matched() {
+        unsafe { reg.rs485_ctl.write(ctl.clear_rs485_address_matched()) };
+        true
+    } else {
+        false
+    }
+}
+
+#[inline]
+fn set_match_address(reg: &RegisterBlock, address: u8) {
+    unsafe {
+        reg.rs485_addr_match
+            .modify(|v| v.set_match_address(address as u32));
+    }
+}
+
+impl<'a, const I: u8, TX, RX> BlockingSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + WithDe<I>,
+    RX: UartPad<I> + Receive<I>,
+{
+    /// Switch to RS-485 half-duplex mode.
+    ///
+    /// Waits for queued characters to be sent first. [`Self::set_config`]
+    /// replaces the frame format; enable RS-485 again after it to keep
+    /// 9-bit frames.
+    pub fn enable_rs485(&mut self, config: Rs485Config) -> Result<(), ConfigError> {
+        const {
+            assert!(
+                !RX::REQUEST_TO_SEND,
+                "RS-485 mode does not support RTS flow control"
+            )
+        };
+        wait_transmit_idle(self.reg);
+        enable_rs485(self.reg, config)
+    }
+
+    /// Switch back to full-duplex UART mode.
+    ///
+    /// The frame format is kept; use [`Self::set_config`] to leave 9-bit frames.
+    #[inline]
+    pub fn disable_rs485(&mut self) {
+        disable_rs485(self.reg)
+    }
+
+    /// Send `address` as a 9-bit address frame.
+    ///
+    /// Waits for queued characters to be sent first; characters written
+    /// after it are sent as data frames. Fails with
+    /// [`ConfigError::NotNineBit`] unless RS-485 was enabled with 9-bit
+    /// frames.
+    pub fn send_address(&mut self, address: u8) -> Result<(), ConfigError> {
+        check_nine_bit(self.reg)?;
+        wait_transmit_idle(self.reg);
+        send_address(self.reg, address)
+    }
+
+    /// Check if the RS-485 bus is idle.
+    ///
+    /// Only meaningful when bus idle detection is enabled.
+    #[inline]
+    pub fn is_bus_idle(&self) -> bool {
+        is_bus_idle(self.reg)
+    }
+
+    /// Check and clear whether a matching RS-485 address was received.
+    #[inline]
+    pub fn take_address_matched(&mut self) -> bool {
+        take_address_matched(self.reg)
+    }
+
+    /// Change the RS-485 address to match.
+    #[inline]
+    pub fn set_match_address(&mut self, address: u8) {
+        set_match_address(self.reg, address)
+    }
+}
+
+#[cfg(feature = "clic_interrupts")]
+impl<'a, const I: u8, TX, RX> AsyncSerial<'a, I, TX, RX>
+where
+    TX: UartPad<I> + WithDe<I>,
+    RX: UartPad<I> + Receive<I>,
+    Uart<I>: UartInterrupt<I>,
+{
+    /// Switch to RS-485 half-duplex mode.
+    ///
+    /// Waits for buffered characters to be sent first. [`Self::set_config`]
+    /// replaces the frame format; enable RS-485 again after it to keep
+    /// 9-bit frames.
+    pub async fn enable_rs485(&mut self, config: Rs485Config) -> Result<(), ConfigError> {
+        const {
+            assert!(
+                !RX::REQUEST_TO_SEND,
+                "RS-485 mode does not support RTS flow control"
+            )
+        };
+        flush_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state()).await;
+        wait_transmit_idle(self.reg);
+        enable_rs485(self.reg, config)
+    }
+
+    /// Switch back to full-duplex UART mode.
+    ///
+    /// The frame format is kept; use [`Self::set_config`] to leave 9-bit frames.
+    #[inline]
+    pub fn disable_rs485(&mut self) {
+        disable_rs485(self.reg)
+    }
+
+    /// Send `address` as a 9-bit address frame.
+    ///
+    /// Waits for buffered characters to be sent first; characters written
+    /// after it are sent as data frames. Fails with
+    /// [`ConfigError::NotNineBit`] unless RS-485 was enabled with 9-bit
+    /// frames.
+    pub async fn send_address(&mut self, address: u8) -> Result<(), ConfigError> {
+        check_nine_bit(self.reg)?;
+        flush_buffered(self.reg, <Uart<I> as UartInterrupt<I>>::state()).await;
+        wait_transmit_idle(self.reg);
+        send_address(self.reg, address)
+    }
+
+    /// Check if the RS-485 bus is idle.
+    ///
+    /// Only meaningful when bus idle detection is enabled.
+    #[inline]
+    pub fn is_bus_idle(&self) -> bool {
+        is_bus_idle(self.reg)
+    }
+
+    /// Check and clear whether a matching RS-485 address was received.
+    #[inline]
+    pub fn take_address_matched(&mut self) -> bool {
+        take_address_matched(self.reg)
+    }
+
+    /// Change the RS-485 address to match.
+    #[inline]
+    pub fn set_match_address(&mut self, address: u8) {
+        set_match_address(self.reg, address)
+    }
+}
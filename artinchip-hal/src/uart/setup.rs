@@ -0,0 +1,216 @@
+//! UART clock and line configuration.
+
+use volatile_register::RW;
+
+use super::config::{StopBits, UartConfig};
+use super::error::ConfigError;
+use super::modem::configure_flow_control;
+use super::pad::{Receive, Transmit};
+use super::register::RegisterBlock;
+use crate::cmu::{Cmu, NormalModuleClock};
+
+/// Highest UART module clock; matches the 48 MHz used by the vendor SDK.
+const MAX_MODULE_CLK: u32 = 48_000_000;
+/// Polls to wait for the hardware to apply a halted configuration change.
+const HALT_UPDATE_SPIN_LIMIT: usize = 100_000;
+
+/// Get the CMU clock register of UART instance `I`.
+pub(crate) fn clock_register<const I: u8>(cmu: &Cmu) -> &'static RW<NormalModuleClock> {
+    let clk = cmu.register_block();
+    match I {
+        0 => &clk.clock_uart0,
+        1 => &clk.clock_uart1,
+        2 => &clk.clock_uart2,
+        3 => &clk.clock_uart3,
+        4 => &clk.clock_uart4,
+        5 => &clk.clock_uart5,
+        6 => &clk.clock_uart6,
+        7 => &clk.clock_uart7,
+        _ => panic!("Invalid UART index"),
+    }
+}
+
+/// Enable and reset the module clock of UART instance `I`, returning its
+/// frequency in Hz.
+///
+/// The module clock is divided from PLL_INT1 to at most [`MAX_MODULE_CLK`].
+pub(crate) fn enable_clock<const I: u8>(cmu: &mut Cmu) -> u32 {
+    let pll_int1 = cmu.pll_int1_freq().0;
+    let div = pll_int1.div_ceil(MAX_MODULE_CLK).clamp(1, 32) - 1;
+    let uart_clk = clock_register::<I>(cmu);
+    unsafe {
+        // Initialize module clock.
+        // Reference: https://aicdoc.artinchip.com/topics/ic/cmu/cmu-function2-d13x.html#topic_yvp_f24_4bc__table_qb3_bn5_ydc
+        uart_clk.modify(|v| v.set_module_clk_div(div as u8).enable_module_clk());
+        uart_clk.modify(|v| v.enable_bus_clk());
+        uart_clk.modify(|v| v.enable_module_reset());
+        riscv::asm::delay(500);
+        uart_clk.modify(|v| v.disable_module_reset());
+    }
+    pll_int1 / (div + 1)
+}
+
+/// Compute the divisor closest to `config.baud_rate` from module clock `clk`.
+pub(crate) fn baud_divisor(clk: u32, config: &UartConfig) -> Result<u16, ConfigError> {
+    let requested = config.baud_rate.0;
+    if requested == 0 {
+        return Err(ConfigError::BaudRate {
+            requested,
+            achieved: 0,
+        });
+    }
+    let sample_rate = 16 * requested as u64;
+    let divisor = ((clk as u64 + sample_rate / 2) / sample_rate).clamp(1, u16::MAX as u64);
+    let achieved = (clk as u64 / (16 * divisor)) as u32;
+    let deviation = achieved.abs_diff(requested) as u64 * 1000 / requested as u64;
+    if deviation > config.baud_tolerance_permille as u64 {
+        return Err(ConfigError::BaudRate {
+            requested,
+            achieved,
+        });
+    }
+    Ok(divisor as u16)
+}
+
+/// Read the baud rate divisor in use.
+#[inline]
+pub(crate) fn current_divisor(reg: &RegisterBlock) -> u16 {
+    u16::from_le_bytes([
+        reg.dbg_dll.read().debug_dll(),
+        reg.dbg_dlh.read().debug_dlh(),
+    ])
+}
+
+/// Apply baud rate, line and flow control settings.
+///
+/// Nothing is changed if the baud rate is not achievable. If the hardware
+/// does not apply the new divisor and line control in time, the previous
+/// ones are written back and flow control is left unchanged.
+pub(crate) fn apply_config<const I: u8, TX, RX>(
+    reg: &RegisterBlock,
+    config: &UartConfig,
+    clk: u32,
+) -> Result<(), ConfigError>
+where
+    TX: Transmit<I>,
+    RX: Receive<I>,
+{
+    let divisor = baud_divisor(clk, config)?;
+    let uart16550 = &reg.uart16550;
+
+    // Save divisor and line control for rollback
+    let old_divisor = current_divisor(reg);
+    let old_lcr = uart16550.lcr().read();
+
+    // Write baud rate divisor and line control register
+    let result = update_halted(reg, || {
+        uart16550.write_divisor(divisor);
+        let lcr = uart16550.lcr().read();
+        uart16550.lcr().write(
+            lcr.set_char_len(config.data_bits.to_char_len())
+                .set_one_stop_bit(config.stop_bits == StopBits::One)
+                .set_parity(config.parity.to_parity()),
+        );
+        // Leave RS-485 9-bit frames
+        unsafe { reg.line_control().modify(|v| v.disable_stick_parity()) };
+    });
+    if result.is_err() {
+        let _ = update_halted(reg, || {
+            uart16550.write_divisor(old_divisor);
+            uart16550.lcr().write(old_lcr);
+        });
+        return result;
+    }
+
+    configure_flow_control::<I, TX, RX>(reg);
+    Ok(())
+}
+
+/// Change the divisor or line control registers in `update` while
+/// transmission is halted.
+///
+/// The new settings take effect between characters even if the UART is busy.
+pub(crate) fn update_halted(reg: &RegisterBlock, update: impl FnOnce()) -> Result<(), ConfigError> {
+    // Halt uart for configuration
+    unsafe {
+        reg.halt
+            .modify(|v| v.halt_tx(true).set_halt_change_config_at_busy(true));
+    }
+
+    update();
+
+    // Update HALT register to apply configuration
+    unsafe {
+        reg.halt.modify(|v| v.set_halt_change_update(true));
+    }
+    let mut result = Ok(());
+    let mut spins = 0;
+    while reg.halt.read().is_halt_change_update_pending() {
+        core::hint::spin_loop();
+        spins += 1;
+        if spins >= HALT_UPDATE_SPIN_LIMIT {
+            result = Err(ConfigError::UpdateTimeout);
+            break;
+        }
+    }
+    unsafe {
+        reg.halt
+            .modify(|v| v.set_halt_change_config_at_busy(false).halt_tx(false));
+    }
+    result
+}
+
+/// Wait until all queued characters have been sent.
+pub(crate) fn wait_transmit_idle(reg: &RegisterBlock) {
+    while !reg.usr.read().is_transmit_fifo_empty() {
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::baud_divisor;
+    use crate::uart::{ConfigError, UartConfig};
+    use embedded_time::rate::Baud;
+
+    const CLK: u32 = 48_000_000;
+
+    fn config(baud_rate: u32) -> UartConfig {
+        UartConfig {
+            baud_rate: Baud(baud_rate),
+            ..UartConfig::default()
+        }
+    }
+
+    #[test]
+    fn baud_divisor_exact() {
+        assert_eq!(baud_divisor(CLK, &config(500_000)), Ok(6));
+        assert_eq!(baud_divisor(CLK, &config(3_000_000)), Ok(1));
+    }
+
+    #[test]
+    fn baud_divisor_rounded() {
+        // 48 MHz / (16 * 115200) = 26.04, achieving 115384 Bd.
+        assert_eq!(baud_divisor(CLK, &config(115_200)), Ok(26));
+        // 48 MHz / (16 * 57600) = 52.08, achieving 57692 Bd.
+        assert_eq!(baud_divisor(CLK, &config(57_600)), Ok(52));
+    }
+
+    #[test]
+    fn baud_divisor_out_of_tolerance() {
+        assert_eq!(
+            baud_divisor(CLK, &config(2_000_000)),
+            Err(ConfigError::BaudRate {
+                requested: 2_000_000,
+                achieved: 1_500_000,
+            })
+        );
+        assert_eq!(
+            baud_divisor(CLK, &config(0)),
+            Err(ConfigError::BaudRate {
+                requested: 0,
+                achieved: 0,
+            })
+        );
+    }
+}
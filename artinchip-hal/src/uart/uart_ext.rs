@@ -10,6 +10,13 @@ use {super::instance::*, super::non_blocking::*, crate::interrupt::clic::typelev
 
 pub trait UartExt<'a, const I: u8> {
     /// Greats a blocking UART interface with the specified pads.
+    ///
+    /// Wrap the pads in [`WithCts`](super::WithCts) and [`WithRts`](super::WithRts)
+    /// for hardware flow control, or pair `tx` with a driver enable pad for RS-485.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` can not be applied, see [`BlockingSerial::new`].
     fn new_blocking<TX, RX>(
         self,
         tx: TX,
@@ -21,11 +28,19 @@ pub trait UartExt<'a, const I: u8> {
         TX: UartPad<I> + Transmit<I>,
         RX: UartPad<I> + Receive<I>;
     /// Creates a non-blocking UART interface with the specified pads.
+    ///
+    /// `buffers` hold data between the task and the interrupt handler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` can not be applied or the transmit buffer is empty,
+    /// see [`AsyncSerial::new`].
     #[cfg(feature = "clic_interrupts")]
     fn new_async<TX, RX, IRQS>(
         self,
         tx: TX,
         rx: RX,
+        buffers: AsyncBuffers,
         config: UartConfig,
         cmu: &mut Cmu,
         _irqs: IRQS,
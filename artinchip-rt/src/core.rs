@@ -1,2 +1,4 @@
 pub mod cache;
+pub mod perf;
+pub mod power;
 pub mod trap;
@@ -0,0 +1,225 @@
+//! ArtInChip hardware performance counters.
+//!
+//! Wraps the machine-mode `mcycle`, `minstret` and `mhpmcounter3..=31`
+//! counters and the `mhpmevent3..=31` event selectors. Event numbers are core
+//! specific; see the XuanTie core user manual for the event table.
+
+use ::core::arch::asm;
+
+const MCOUNTINHIBIT: u16 = 0x320;
+const MHPMEVENT3: u16 = 0x323;
+const MCYCLE: u16 = 0xB00;
+const MINSTRET: u16 = 0xB02;
+const MHPMCOUNTER3: u16 = 0xB03;
+#[cfg(target_pointer_width = "32")]
+const COUNTER_HIGH: u16 = 0x80;
+
+/// Expands `$body` with `$csr` bound to the CSR number `$base + $n` for
+/// `$n` in `3..=31`, since CSR numbers must be immediates.
+macro_rules! with_hpm_csr {
+    ($n:expr, $base:expr, |$csr:ident| $body:expr) => {
+        with_hpm_csr!(@arms $n, $base, $csr, $body, [
+            3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+        ])
+    };
+    (@arms $n:expr, $base:expr, $csr:ident, $body:expr, [$($i:literal)*]) => {
+        match $n {
+            $(
+                $i => {
+                    const $csr: u16 = $base + $i - 3;
+                    $body
+                }
+            )*
+            _ => panic!("Invalid hardware performance counter: {}", $n),
+        }
+    };
+}
+
+#[inline(always)]
+fn read_counter<const CSR: u16>() -> u64 {
+    #[cfg(target_pointer_width = "32")]
+    loop {
+        let (hi, lo, hi2): (usize, usize, usize);
+        unsafe {
+            asm!(
+                "csrr {0}, {high}",
+                "csrr {1}, {low}",
+                "csrr {2}, {high}",
+                out(reg) hi,
+                out(reg) lo,
+                out(reg) hi2,
+                low = const CSR,
+                high = const CSR + COUNTER_HIGH,
+                options(nomem, nostack),
+            );
+        }
+        if hi == hi2 {
+            return ((hi as u64) << 32) | lo as u64;
+        }
+    }
+    #[cfg(target_pointer_width = "64")]
+    {
+        let val: usize;
+        unsafe {
+            asm!("csrr {0}, {csr}", out(reg) val, csr = const CSR, options(nomem, nostack));
+        }
+        val as u64
+    }
+}
+
+#[inline(always)]
+fn write_counter<const CSR: u16>(val: u64) {
+    #[cfg(target_pointer_width = "32")]
+    unsafe {
+        // Clear the low word first so it cannot carry into the new high word.
+        asm!(
+            "csrw {low}, zero",
+            "csrw {high}, {1}",
+            "csrw {low}, {0}",
+            in(reg) val as usize,
+            in(reg) (val >> 32) as usize,
+            low = const CSR,
+            high = const CSR + COUNTER_HIGH,
+            options(nomem, nostack),
+        );
+    }
+    #[cfg(target_pointer_width = "64")]
+    unsafe {
+        asm!("csrw {csr}, {0}", in(reg) val as usize, csr = const CSR, options(nomem, nostack));
+    }
+}
+
+/// Get the number of clock cycles executed by the core (`mcycle`).
+#[inline]
+pub fn cycle() -> u64 {
+    read_counter::<MCYCLE>()
+}
+
+/// Set the cycle counter.
+#[inline]
+pub fn set_cycle(val: u64) {
+    write_counter::<MCYCLE>(val)
+}
+
+/// Get the number of instructions retired by the core (`minstret`).
+#[inline]
+pub fn instret() -> u64 {
+    read_counter::<MINSTRET>()
+}
+
+/// Set the retired instruction counter.
+#[inline]
+pub fn set_instret(val: u64) {
+    write_counter::<MINSTRET>(val)
+}
+
+/// Get the value of hardware performance counter `n` (`mhpmcounter3..=31`).
+///
+/// # Panics
+///
+/// Panics if `n` is not in `3..=31`.
+#[inline]
+pub fn hpm_counter(n: u8) -> u64 {
+    with_hpm_csr!(n, MHPMCOUNTER3, |CSR| read_counter::<CSR>())
+}
+
+/// Set the value of hardware performance counter `n`.
+///
+/// # Panics
+///
+/// Panics if `n` is not in `3..=31`.
+#[inline]
+pub fn set_hpm_counter(n: u8, val: u64) {
+    with_hpm_csr!(n, MHPMCOUNTER3, |CSR| write_counter::<CSR>(val))
+}
+
+/// Get the event counted by hardware performance counter `n` (`mhpmevent3..=31`).
+///
+/// # Panics
+///
+/// Panics if `n` is not in `3..=31`.
+#[inline]
+pub fn hpm_event(n: u8) -> usize {
+    with_hpm_csr!(n, MHPMEVENT3, |CSR| {
+        let val: usize;
+        unsafe {
+            asm!("csrr {0}, {csr}", out(reg) val, csr = const CSR, options(nomem, nostack));
+        }
+        val
+    })
+}
+
+/// Select the event counted by hardware performance counter `n`.
+///
+/// Event 0 stops the counter from counting.
+///
+/// # Panics
+///
+/// Panics if `n` is not in `3..=31`.
+#[inline]
+pub fn set_hpm_event(n: u8, event: usize) {
+    with_hpm_csr!(n, MHPMEVENT3, |CSR| unsafe {
+        asm!("csrw {csr}, {0}", in(reg) event, csr = const CSR, options(nomem, nostack));
+    })
+}
+
+/// Stop the counters selected by `mask` (`mcountinhibit`).
+///
+/// Bit 0 is `mcycle`, bit 2 is `minstret` and bit `n` is `mhpmcounter{n}`.
+#[inline]
+pub fn inhibit_counters(mask: u32) {
+    unsafe {
+        asm!("csrs {csr}, {0}", in(reg) mask as usize, csr = const MCOUNTINHIBIT, options(nomem, nostack));
+    }
+}
+
+/// Resume the counters selected by `mask`.
+#[inline]
+pub fn uninhibit_counters(mask: u32) {
+    unsafe {
+        asm!("csrc {csr}, {0}", in(reg) mask as usize, csr = const MCOUNTINHIBIT, options(nomem, nostack));
+    }
+}
+
+/// Counter deltas collected by [`measure`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Measurement<const N: usize> {
+    /// Clock cycles elapsed.
+    pub cycles: u64,
+    /// Instructions retired.
+    pub instructions: u64,
+    /// Deltas of the requested hardware performance counters, in order.
+    pub events: [u64; N],
+}
+
+/// Run `f` and measure the cycles, instructions and hardware performance
+/// counters `hpm` consumed by it.
+///
+/// The counters must already be configured with [`set_hpm_event`]. The
+/// measurement includes a few instructions of counter read overhead.
+///
+/// # Panics
+///
+/// Panics if any of `hpm` is not in `3..=31`.
+#[inline]
+pub fn measure<R, const N: usize>(hpm: [u8; N], f: impl FnOnce() -> R) -> (R, Measurement<N>) {
+    let mut events = hpm.map(hpm_counter);
+    let instructions = instret();
+    let cycles = cycle();
+
+    let ret = f();
+
+    let cycles = cycle().wrapping_sub(cycles);
+    let instructions = instret().wrapping_sub(instructions);
+    for (event, &n) in events.iter_mut().zip(hpm.iter()) {
+        *event = hpm_counter(n).wrapping_sub(*event);
+    }
+    (
+        ret,
+        Measurement {
+            cycles,
+            instructions,
+            events,
+        },
+    )
+}
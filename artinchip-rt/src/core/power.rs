@@ -0,0 +1,302 @@
+//! ArtInChip low-power idle control.
+//!
+//! XuanTie cores enter a low-power state on `wfi`. Which state is entered is
+//! selected by the `LPMD` field in the T-Head `mexstatus` register, while
+//! `WFEEN` additionally allows the external wake-up event signal to leave it.
+//!
+//! The core wakes whenever an interrupt enabled in CLIC becomes pending, even if
+//! `mstatus.MIE` is cleared, so wake-up sources are configured through CLIC.
+
+use ::core::arch::asm;
+use artinchip_hal::cmu::{Cmu, NormalModuleClock, SimpleModule2Clock, SimpleModule5Clock};
+use volatile_register::RW;
+
+const MEXSTATUS: u16 = 0x7e1;
+const LPMD: usize = 0x3 << 2;
+const WFEEN: usize = 0x1 << 4;
+
+/// Low-power mode entered on `wfi`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SleepMode {
+    /// Deep sleep, core and cache clocks are stopped.
+    Deep = 0,
+    /// Light sleep, only the core pipeline clock is stopped.
+    Light = 1,
+}
+
+/// Select the low-power mode used by subsequent `wfi` instructions.
+#[inline]
+pub fn set_sleep_mode(mode: SleepMode) {
+    unsafe {
+        asm!(
+            "csrc {mexstatus}, {mask}",
+            "csrs {mexstatus}, {val}",
+            mexstatus = const MEXSTATUS,
+            mask = in(reg) LPMD,
+            val = in(reg) (mode as usize) << 2,
+        );
+    }
+}
+
+/// Get the low-power mode used by `wfi`.
+#[inline]
+pub fn sleep_mode() -> SleepMode {
+    let bits: usize;
+    unsafe {
+        asm!("csrr {}, {mexstatus}", out(reg) bits, mexstatus = const MEXSTATUS);
+    }
+    match (bits & LPMD) >> 2 {
+        1 => SleepMode::Light,
+        _ => SleepMode::Deep,
+    }
+}
+
+/// Allow the external wake-up event to leave low-power mode (`WFEEN`).
+#[inline]
+pub fn enable_wake_on_event() {
+    unsafe {
+        asm!("csrs {mexstatus}, {0}", in(reg) WFEEN, mexstatus = const MEXSTATUS);
+    }
+}
+
+/// Only leave low-power mode on pending interrupts.
+#[inline]
+pub fn disable_wake_on_event() {
+    unsafe {
+        asm!("csrc {mexstatus}, {0}", in(reg) WFEEN, mexstatus = const MEXSTATUS);
+    }
+}
+
+/// Wait for interrupt in the currently selected low-power mode.
+#[inline]
+pub fn wfi() {
+    unsafe {
+        asm!("wfi", options(nomem, nostack));
+    }
+}
+
+/// Enter `mode` and wait until a wake-up source fires.
+///
+/// Returns after wake-up; if interrupts are globally enabled, the pending
+/// interrupt handler runs before this function returns.
+#[inline]
+pub fn sleep(mode: SleepMode) {
+    set_sleep_mode(mode);
+    wfi();
+}
+
+/// Peripheral clocks that can be gated while idle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PeripheralClock {
+    /// Direct Memory Access.
+    Dma,
+    /// Crypto Engine.
+    Ce,
+    /// Expanded Serial Peripheral Interface.
+    Xspi,
+    /// Quad Serial Peripheral Interface 0..=3.
+    Qspi(u8),
+    /// Secure Digital Host Controller 0..=1.
+    Sdmc(u8),
+    /// Serial Peripheral Interface Encryption.
+    SpiEnc,
+    /// Universal Asynchronous Receiver-Transmitter 0..=7.
+    Uart(u8),
+    /// Inter-Integrated Circuit 0..=3.
+    I2c(u8),
+    /// Controller Area Network 0..=1.
+    Can(u8),
+    /// Pulse Width Modulation.
+    Pwm,
+    /// Generic Timer Controller.
+    Gtc,
+    /// General Purpose ADC.
+    Gpai,
+    /// Thermal Sensor.
+    Ths,
+    /// Consumer Infrared.
+    Cir,
+}
+
+/// Peripheral clock with an instance index the SoC does not have.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InvalidPeripheralClock(pub PeripheralClock);
+
+impl ::core::fmt::Display for InvalidPeripheralClock {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "invalid peripheral clock: {:?}", self.0)
+    }
+}
+
+impl ::core::error::Error for InvalidPeripheralClock {}
+
+/// CMU register of a peripheral clock.
+enum ClockRegister {
+    Simple(&'static RW<SimpleModule2Clock>),
+    Normal(&'static RW<NormalModuleClock>),
+    Enc(&'static RW<SimpleModule5Clock>),
+}
+
+impl ClockRegister {
+    fn gate(&self) {
+        unsafe {
+            match self {
+                ClockRegister::Simple(r) => r.modify(|v| v.disable_bus_clk().enable_module_reset()),
+                ClockRegister::Normal(r) => r.modify(|v| {
+                    v.disable_module_clk()
+                        .disable_bus_clk()
+                        .enable_module_reset()
+                }),
+                ClockRegister::Enc(r) => r.modify(|v| {
+                    v.disable_module_clk()
+                        .disable_bus_clk()
+                        .enable_module_reset()
+                }),
+            }
+        }
+    }
+
+    fn is_bus_clk_enabled(&self) -> bool {
+        match self {
+            ClockRegister::Simple(r) => r.read().is_bus_clk_enabled(),
+            ClockRegister::Normal(r) => r.read().is_bus_clk_enabled(),
+            ClockRegister::Enc(r) => r.read().is_bus_clk_enabled(),
+        }
+    }
+}
+
+/// Get the CMU register of `clock`, or `None` if its index is out of range.
+fn clock_register(cmu: &Cmu, clock: PeripheralClock) -> Option<ClockRegister> {
+    use ClockRegister::{Enc, Normal, Simple};
+    let r = cmu.register_block();
+    Some(match clock {
+        PeripheralClock::Dma => Simple(&r.clock_dma),
+        PeripheralClock::Ce => Normal(&r.clock_ce),
+        PeripheralClock::Xspi => Normal(&r.clock_xspi),
+        PeripheralClock::Qspi(0) => Normal(&r.clock_qspi0),
+        PeripheralClock::Qspi(1) => Normal(&r.clock_qspi1),
+        PeripheralClock::Qspi(2) => Normal(&r.clock_qspi2),
+        PeripheralClock::Qspi(3) => Normal(&r.clock_qspi3),
+        PeripheralClock::Sdmc(0) => Normal(&r.clock_sdmc0),
+        PeripheralClock::Sdmc(1) => Normal(&r.clock_sdmc1),
+        PeripheralClock::SpiEnc => Enc(&r.clock_spi_enc),
+        PeripheralClock::Uart(0) => Normal(&r.clock_uart0),
+        PeripheralClock::Uart(1) => Normal(&r.clock_uart1),
+        PeripheralClock::Uart(2) => Normal(&r.clock_uart2),
+        PeripheralClock::Uart(3) => Normal(&r.clock_uart3),
+        PeripheralClock::Uart(4) => Normal(&r.clock_uart4),
+        PeripheralClock::Uart(5) => Normal(&r.clock_uart5),
+        PeripheralClock::Uart(6) => Normal(&r.clock_uart6),
+        PeripheralClock::Uart(7) => Normal(&r.clock_uart7),
+        PeripheralClock::I2c(0) => Simple(&r.clock_i2c0),
+        PeripheralClock::I2c(1) => Simple(&r.clock_i2c1),
+        PeripheralClock::I2c(2) => Simple(&r.clock_i2c2),
+        PeripheralClock::I2c(3) => Simple(&r.clock_i2c3),
+        PeripheralClock::Can(0) => Simple(&r.clock_can0),
+        PeripheralClock::Can(1) => Simple(&r.clock_can1),
+        PeripheralClock::Pwm => Normal(&r.clock_pwm),
+        PeripheralClock::Gtc => Simple(&r.clock_gtc),
+        PeripheralClock::Gpai => Simple(&r.clock_gpai),
+        PeripheralClock::Ths => Simple(&r.clock_ths),
+        PeripheralClock::Cir => Simple(&r.clock_cir),
+        _ => return None,
+    })
+}
+
+/// Gate the bus and module clocks of an unused peripheral and hold it in reset.
+///
+/// The peripheral driver re-enables its clock on construction, so this must
+/// only be called for peripherals that are not currently driven.
+pub fn gate_peripheral_clock(
+    cmu: &mut Cmu,
+    clock: PeripheralClock,
+) -> Result<(), InvalidPeripheralClock> {
+    let reg = clock_register(cmu, clock).ok_or(InvalidPeripheralClock(clock))?;
+    reg.gate();
+    Ok(())
+}
+
+/// Check whether the bus clock of a peripheral is gated.
+pub fn is_peripheral_clock_gated(
+    cmu: &Cmu,
+    clock: PeripheralClock,
+) -> Result<bool, InvalidPeripheralClock> {
+    let reg = clock_register(cmu, clock).ok_or(InvalidPeripheralClock(clock))?;
+    Ok(!reg.is_bus_clk_enabled())
+}
+
+#[cfg(feature = "interrupts")]
+pub use wake::*;
+
+#[cfg(feature = "interrupts")]
+mod wake {
+    use crate::gpio::Input;
+    use artinchip_hal::gpio::GeneralIrqMode;
+    use artinchip_hal::interrupt::clic::{self, typelevel, typelevel::Interrupt as _};
+    #[cfg(not(feature = "m6800"))]
+    use artinchip_hal::rtc::RealTime;
+
+    /// Allow interrupt `I` to wake the core from `wfi`.
+    ///
+    /// The interrupt is enabled in CLIC; whether its handler also runs on
+    /// wake-up depends on `mstatus.MIE`.
+    #[inline]
+    pub fn enable_wake_interrupt<I: typelevel::Interrupt>() {
+        I::enable();
+    }
+
+    /// Stop interrupt `I` from waking the core.
+    #[inline]
+    pub fn disable_wake_interrupt<I: typelevel::Interrupt>() {
+        I::disable();
+    }
+
+    /// Program the RTC alarm to `alarm` seconds and use it as a wake-up source.
+    ///
+    /// Clear the alarm with [`RealTime::clear_alarm_pending`] after wake-up.
+    #[cfg(not(feature = "m6800"))]
+    pub fn enable_rtc_alarm_wake(rtc: &mut RealTime<'_>, alarm: u32) {
+        rtc.clear_alarm_pending();
+        typelevel::RTC::clear_pending();
+        rtc.set_alarm(alarm);
+        enable_wake_interrupt::<typelevel::RTC>();
+    }
+
+    /// Stop the RTC alarm from waking the core.
+    #[cfg(not(feature = "m6800"))]
+    pub fn disable_rtc_alarm_wake() {
+        disable_wake_interrupt::<typelevel::RTC>();
+    }
+
+    /// Use an edge or level on an input pin as a wake-up source.
+    ///
+    /// Only GPIO groups A to E are routed to CLIC. The pin interrupt must be
+    /// cleared after wake-up, otherwise level modes wake the core again at once.
+    pub fn enable_gpio_wake<const G: char, const N: u8>(
+        pin: &mut Input<'_, G, N>,
+        mode: GeneralIrqMode,
+    ) {
+        pin.enable_interrupt(mode);
+        unsafe { clic::enable_interrupt(gpio_group_irq::<G>()) };
+    }
+
+    /// Stop an input pin from waking the core.
+    ///
+    /// The group interrupt stays enabled in CLIC, since other pins of the same
+    /// group may still be used as wake-up sources.
+    pub fn disable_gpio_wake<const G: char, const N: u8>(pin: &mut Input<'_, G, N>) {
+        pin.disable_interrupt();
+    }
+
+    #[inline]
+    const fn gpio_group_irq<const G: char>() -> u8 {
+        const {
+            assert!(
+                matches!(G, 'A'..='E'),
+                "Only GPIO groups A to E can wake the core"
+            )
+        };
+        typelevel::GPIO_GRP_A::IRQ + (G as u8 - b'A')
+    }
+}
@@ -0,0 +1,12 @@
+//! Embassy executor and time driver support.
+//!
+//! The thread mode [`Executor`] sleeps in `wfi` while no task is ready, and
+//! [`InterruptExecutor`] polls its tasks from a spare CLIC interrupt so they
+//! can preempt thread mode. Timers are driven by CLINT `mtimecmp` through the
+//! `CPU_TIMER` interrupt, which must be bound to [`TimerInterruptHandler`].
+
+mod executor;
+mod time_driver;
+
+pub use executor::{Executor, InterruptExecutor};
+pub use time_driver::{TICK_HZ, TimerInterruptHandler, init_time_driver};
@@ -0,0 +1,172 @@
+//! Embassy executors for XuanTie cores.
+
+use ::core::cell::UnsafeCell;
+use ::core::marker::PhantomData;
+use ::core::mem::MaybeUninit;
+use ::core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use artinchip_hal::interrupt::clic::{self, typelevel};
+use embassy_executor::{SendSpawner, Spawner, raw};
+
+/// Pender context of the thread mode executor.
+///
+/// Interrupt executors use their CLIC interrupt number as context, which is
+/// always below this value.
+const THREAD_PENDER: usize = usize::MAX;
+
+/// Set when a thread mode task was woken; `wfi` is skipped while set.
+static SIGNAL_WORK_THREAD_MODE: AtomicBool = AtomicBool::new(false);
+
+#[unsafe(export_name = "__pender")]
+fn __pender(context: *mut ()) {
+    let context = context as usize;
+    if context == THREAD_PENDER {
+        SIGNAL_WORK_THREAD_MODE.store(true, Ordering::SeqCst);
+    } else {
+        unsafe { clic::set_pending(context as u8) };
+    }
+}
+
+/// Thread mode executor.
+///
+/// Polls tasks in thread mode and enters the low-power mode selected by
+/// [`crate::core::power::set_sleep_mode`] when no task is ready.
+pub struct Executor {
+    inner: raw::Executor,
+    not_send: PhantomData<*mut ()>,
+}
+
+impl Executor {
+    /// Create a new thread mode executor.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: raw::Executor::new(THREAD_PENDER as *mut ()),
+            not_send: PhantomData,
+        }
+    }
+
+    /// Run the executor.
+    ///
+    /// The `init` closure is called with a [`Spawner`] to spawn the initial
+    /// tasks. This function never returns.
+    pub fn run(&'static mut self, init: impl FnOnce(Spawner)) -> ! {
+        init(self.inner.spawner());
+
+        loop {
+            unsafe {
+                self.inner.poll();
+            }
+            // An interrupt arriving between the check and `wfi` still leaves the
+            // core pending, so `wfi` returns at once and the handler runs after
+            // the critical section.
+            critical_section::with(|_| {
+                if !SIGNAL_WORK_THREAD_MODE.swap(false, Ordering::SeqCst) {
+                    crate::core::power::wfi();
+                }
+            });
+        }
+    }
+}
+
+impl Default for Executor {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Interrupt mode executor.
+///
+/// Tasks are polled from the handler of a CLIC interrupt not used by any
+/// peripheral; waking a task pends this interrupt from software. Bind a
+/// handler that calls [`InterruptExecutor::on_interrupt`] to the chosen
+/// interrupt with `clic_bind_interrupts!`, then call
+/// [`InterruptExecutor::start`] after the CLIC is initialized.
+pub struct InterruptExecutor {
+    started: AtomicBool,
+    irq: AtomicU8,
+    executor: UnsafeCell<MaybeUninit<raw::Executor>>,
+}
+
+unsafe impl Send for InterruptExecutor {}
+unsafe impl Sync for InterruptExecutor {}
+
+impl InterruptExecutor {
+    /// Create a new, not started interrupt executor.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            started: AtomicBool::new(false),
+            irq: AtomicU8::new(0),
+            executor: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Poll the executor's tasks.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called from the handler of the interrupt passed to
+    /// [`InterruptExecutor::start`].
+    pub unsafe fn on_interrupt(&'static self) {
+        if !self.started.load(Ordering::Acquire) {
+            return;
+        }
+        unsafe {
+            clic::clear_pending(self.irq.load(Ordering::Relaxed));
+            (*self.executor.get()).assume_init_ref().poll();
+        }
+    }
+
+    /// Start the executor on interrupt `I` and return its spawner.
+    ///
+    /// The interrupt is switched to positive edge trigger so that software
+    /// pending is retained, given `priority` and enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the executor was already started.
+    pub fn start<I: typelevel::Interrupt>(&'static self, priority: u8) -> SendSpawner {
+        if self.started.load(Ordering::Acquire) {
+            panic!("InterruptExecutor::start() called multiple times on the same executor.");
+        }
+
+        unsafe {
+            (*self.executor.get()).write(raw::Executor::new(I::IRQ as usize as *mut ()));
+        }
+        self.irq.store(I::IRQ, Ordering::Relaxed);
+        self.started.store(true, Ordering::Release);
+
+        unsafe {
+            clic::set_interrupt_attribute(I::IRQ, false, 1, 3);
+            clic::set_priority(I::IRQ, priority);
+        }
+        I::clear_pending();
+        I::enable();
+
+        unsafe { (*self.executor.get()).assume_init_ref() }
+            .spawner()
+            .make_send()
+    }
+
+    /// Get a spawner of the running executor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the executor is not started.
+    pub fn spawner(&'static self) -> SendSpawner {
+        if !self.started.load(Ordering::Acquire) {
+            panic!("InterruptExecutor::spawner() called on uninitialized executor.");
+        }
+        unsafe { (*self.executor.get()).assume_init_ref() }
+            .spawner()
+            .make_send()
+    }
+}
+
+impl Default for InterruptExecutor {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,105 @@
+//! Embassy time driver on CLINT `mtime`/`mtimecmp`.
+
+use ::core::cell::RefCell;
+use ::core::ptr::null_mut;
+use ::core::sync::atomic::{AtomicPtr, Ordering};
+use ::core::task::Waker;
+use artinchip_hal::clint::{Clint, MachineTimer};
+use artinchip_hal::interrupt::clic::typelevel::{self, Interrupt as _};
+use critical_section::{CriticalSection, Mutex};
+use embassy_time_driver::Driver;
+use embassy_time_queue_utils::Queue;
+
+/// Tick rate of the time driver.
+///
+/// CLINT `mtime` follows the GTC system counter, which must be running at
+/// `CntFreq::Freq4M` (see `GtcExt::new_timer_delay`).
+pub const TICK_HZ: u64 = 4_000_000;
+
+struct ClintDriver {
+    timer: AtomicPtr<MachineTimer>,
+    queue: Mutex<RefCell<Queue>>,
+}
+
+impl ClintDriver {
+    #[inline]
+    fn timer(&self) -> Option<&'static MachineTimer> {
+        unsafe { self.timer.load(Ordering::Relaxed).as_ref() }
+    }
+
+    fn set_mtimecmp(&self, val: u64) {
+        if let Some(timer) = self.timer() {
+            timer.set_mtimecmp(val);
+        }
+    }
+
+    /// Program the alarm; returns `false` if `at` has already passed.
+    fn set_alarm(&self, _cs: CriticalSection, at: u64) -> bool {
+        if at == u64::MAX {
+            self.set_mtimecmp(u64::MAX);
+            return true;
+        }
+        self.set_mtimecmp(at);
+        if self.now() >= at {
+            self.set_mtimecmp(u64::MAX);
+            return false;
+        }
+        true
+    }
+
+    fn on_interrupt(&self) {
+        critical_section::with(|cs| {
+            self.set_mtimecmp(u64::MAX);
+            let mut queue = self.queue.borrow(cs).borrow_mut();
+            let mut next = queue.next_expiration(self.now());
+            while !self.set_alarm(cs, next) {
+                next = queue.next_expiration(self.now());
+            }
+        });
+    }
+}
+
+impl Driver for ClintDriver {
+    fn now(&self) -> u64 {
+        self.timer().map_or(0, MachineTimer::mtime)
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow(cs).borrow_mut();
+            if queue.schedule_wake(at, waker) {
+                let mut next = queue.next_expiration(self.now());
+                while !self.set_alarm(cs, next) {
+                    next = queue.next_expiration(self.now());
+                }
+            }
+        });
+    }
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: ClintDriver = ClintDriver {
+    timer: AtomicPtr::new(null_mut()),
+    queue: Mutex::new(RefCell::new(Queue::new())),
+});
+
+/// Start the time driver on `clint`.
+///
+/// Call after `init_clic_and_interrupts` of the binding that routes
+/// `CPU_TIMER` to [`TimerInterruptHandler`].
+pub fn init_time_driver(clint: Clint) {
+    DRIVER.timer.store(
+        clint.machine_timer() as *const _ as *mut _,
+        Ordering::Relaxed,
+    );
+    DRIVER.set_mtimecmp(u64::MAX);
+    typelevel::CPU_TIMER::enable();
+}
+
+/// `CPU_TIMER` interrupt handler of the time driver.
+pub struct TimerInterruptHandler;
+
+impl typelevel::Handler<typelevel::CPU_TIMER> for TimerInterruptHandler {
+    unsafe fn on_interrupt() {
+        DRIVER.on_interrupt();
+    }
+}
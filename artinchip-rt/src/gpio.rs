@@ -1,14 +1,22 @@
 //! General Purpose Input Output (GPIO).
 
+mod flex;
 mod function;
 mod input;
+#[cfg(feature = "interrupts")]
+mod interrupt;
 mod mode;
 mod output;
 mod pad;
 mod pad_ext;
+mod port;
 
+pub use flex::{AnyInput, AnyOutput, Flex, FlexMode};
 pub use function::Function;
 pub use input::Input;
+#[cfg(feature = "interrupts")]
+pub use interrupt::GpioInterruptHandler;
 pub use output::Output;
 pub use pad::GpioPad;
 pub use pad_ext::PadExt;
+pub use port::GpioPort;
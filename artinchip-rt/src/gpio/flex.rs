@@ -0,0 +1,442 @@
+//! Type-erased and runtime configurable GPIO pads.
+
+use super::mode::WithinGpioGroup;
+use super::{Input, Output};
+use artinchip_hal::gpio::*;
+
+/// Operating mode of a [`Flex`] pad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FlexMode {
+    /// Input and output buffers disabled.
+    Disconnected,
+    /// General input.
+    Input,
+    /// Push-pull general output; the pad level can still be read back.
+    Output,
+    /// Open-drain output, emulated by driving low or releasing the pad.
+    OpenDrain,
+}
+
+/// GPIO pad with group and number selected at runtime.
+pub struct Flex<'a> {
+    regs: &'a RegisterBlock,
+    group: u8,
+    pin: u8,
+    mode: FlexMode,
+}
+
+impl<'a> Flex<'a> {
+    const PIN_CONFIG: PinConfig = PinConfig::zeroed()
+        .set_pin_func(1)
+        .set_drive_strength(PinDriveStrength::Level3);
+
+    // Internal constructor from typed pads; takes ownership of the pad's
+    // current configuration.
+    #[inline]
+    pub(crate) fn from_parts(regs: &'a RegisterBlock, group: usize, pin: u8) -> Self {
+        let config = regs.groups[group].pin_config[pin as usize].read();
+        let mode = match (
+            config.is_general_input_enabled(),
+            config.is_general_output_enabled(),
+        ) {
+            (_, true) => FlexMode::Output,
+            (true, false) => FlexMode::Input,
+            (false, false) => FlexMode::Disconnected,
+        };
+        Self {
+            regs,
+            group: group as u8,
+            pin,
+            mode,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn group_regs(&self) -> &'a GpioGroup {
+        &self.regs.groups[self.group as usize]
+    }
+
+    #[inline]
+    fn modify_config(&mut self, f: impl FnOnce(PinConfig) -> PinConfig) {
+        unsafe {
+            self.group_regs().pin_config[self.pin as usize].modify(f);
+        }
+    }
+
+    #[inline]
+    fn drive(&mut self, high: bool) {
+        let group = self.group_regs();
+        let pin = self.pin as usize;
+        unsafe {
+            if high {
+                group.output_set.write(OutputSet::default().set_output(pin));
+            } else {
+                group
+                    .output_clear
+                    .write(OutputClear::default().clear_output(pin));
+            }
+        }
+    }
+
+    /// GPIO group letter of the pad, `'A'` to `'U'`.
+    #[inline]
+    pub fn group(&self) -> char {
+        if self.group == 14 {
+            'U'
+        } else {
+            (b'A' + self.group) as char
+        }
+    }
+
+    /// Pad number within its GPIO group.
+    #[inline]
+    pub fn pin(&self) -> u8 {
+        self.pin
+    }
+
+    /// Current operating mode.
+    #[inline]
+    pub fn mode(&self) -> FlexMode {
+        self.mode
+    }
+
+    /// Configures the pad to operate as an input with `pull`.
+    #[inline]
+    pub fn set_as_input(&mut self, pull: PinPull) {
+        let strength = self.drive_strength();
+        self.modify_config(|_| {
+            Self::PIN_CONFIG
+                .disable_general_output()
+                .enable_general_input()
+                .set_pin_pull(pull)
+                .set_drive_strength(strength)
+        });
+        self.mode = FlexMode::Input;
+    }
+
+    /// Configures the pad to operate as a push-pull output with `pull`,
+    /// starting at level `high`.
+    #[inline]
+    pub fn set_as_output(&mut self, pull: PinPull, high: bool) {
+        let strength = self.drive_strength();
+        self.drive(high);
+        self.modify_config(|_| {
+            Self::PIN_CONFIG
+                .enable_general_output()
+                .enable_general_input()
+                .set_pin_pull(pull)
+                .set_drive_strength(strength)
+        });
+        self.mode = FlexMode::Output;
+    }
+
+    /// Configures the pad to operate as an open-drain output with `pull`,
+    /// starting at level `high`.
+    ///
+    /// The pad is driven low or released; an external or internal pull up
+    /// provides the high level.
+    #[inline]
+    pub fn set_as_open_drain(&mut self, pull: PinPull, high: bool) {
+        let strength = self.drive_strength();
+        self.drive(false);
+        self.modify_config(|_| {
+            let config = Self::PIN_CONFIG
+                .enable_general_input()
+                .set_pin_pull(pull)
+                .set_drive_strength(strength);
+            if high {
+                config.disable_general_output()
+            } else {
+                config.enable_general_output()
+            }
+        });
+        self.mode = FlexMode::OpenDrain;
+    }
+
+    /// Disables both input and output buffers of the pad.
+    #[inline]
+    pub fn set_as_disconnected(&mut self) {
+        self.modify_config(|_| PinConfig::zeroed());
+        self.mode = FlexMode::Disconnected;
+    }
+
+    /// Configures the pin pull.
+    #[inline]
+    pub fn set_pull(&mut self, pull: PinPull) {
+        self.modify_config(|r| r.set_pin_pull(pull));
+    }
+
+    /// Get the pin pull.
+    #[inline]
+    pub fn pull(&self) -> PinPull {
+        self.group_regs().pin_config[self.pin as usize]
+            .read()
+            .pin_pull()
+    }
+
+    /// Configures the pin drive strength.
+    #[inline]
+    pub fn set_drive_strength(&mut self, strength: PinDriveStrength) {
+        self.modify_config(|r| r.set_drive_strength(strength));
+    }
+
+    /// Get the pin drive strength.
+    #[inline]
+    pub fn drive_strength(&self) -> PinDriveStrength {
+        self.group_regs().pin_config[self.pin as usize]
+            .read()
+            .drive_strength()
+    }
+
+    /// Check if the pad level is high.
+    #[inline]
+    pub fn is_high(&self) -> bool {
+        self.group_regs()
+            .input_state
+            .read()
+            .is_high(self.pin as usize)
+    }
+
+    /// Set the output level.
+    ///
+    /// In open-drain mode, high releases the pad.
+    #[inline]
+    pub fn set_level(&mut self, high: bool) {
+        if self.mode == FlexMode::OpenDrain {
+            if high {
+                self.modify_config(|r| r.disable_general_output());
+            } else {
+                self.modify_config(|r| r.enable_general_output());
+            }
+        } else {
+            self.drive(high);
+        }
+    }
+
+    /// Check if the output is set high.
+    #[inline]
+    pub fn is_set_high(&self) -> bool {
+        if self.mode == FlexMode::OpenDrain {
+            !self.group_regs().pin_config[self.pin as usize]
+                .read()
+                .is_general_output_enabled()
+        } else {
+            self.group_regs()
+                .output_config
+                .read()
+                .is_high(self.pin as usize)
+        }
+    }
+
+    /// Free the pad, resetting its configuration.
+    #[inline]
+    pub fn free(mut self) {
+        self.set_as_disconnected();
+    }
+}
+
+impl<'a> embedded_hal::digital::ErrorType for Flex<'a> {
+    type Error = core::convert::Infallible;
+}
+
+impl<'a> embedded_hal::digital::InputPin for Flex<'a> {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(Flex::is_high(self))
+    }
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!Flex::is_high(self))
+    }
+}
+
+impl<'a> embedded_hal::digital::OutputPin for Flex<'a> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set_level(false);
+        Ok(())
+    }
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_level(true);
+        Ok(())
+    }
+}
+
+impl<'a> embedded_hal::digital::StatefulOutputPin for Flex<'a> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(Flex::is_set_high(self))
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!Flex::is_set_high(self))
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        let high = Flex::is_set_high(self);
+        self.set_level(!high);
+        Ok(())
+    }
+}
+
+/// Input mode GPIO pad with group and number erased.
+pub struct AnyInput<'a> {
+    flex: Flex<'a>,
+}
+
+impl<'a> AnyInput<'a> {
+    /// Configures the pin pull.
+    #[inline]
+    pub fn set_pull(&mut self, pull: PinPull) {
+        self.flex.set_pull(pull);
+    }
+
+    /// GPIO group letter of the pad.
+    #[inline]
+    pub fn group(&self) -> char {
+        self.flex.group()
+    }
+
+    /// Pad number within its GPIO group.
+    #[inline]
+    pub fn pin(&self) -> u8 {
+        self.flex.pin()
+    }
+
+    /// Convert into a runtime configurable pad.
+    #[inline]
+    pub fn into_flex(self) -> Flex<'a> {
+        self.flex
+    }
+}
+
+impl<'a> embedded_hal::digital::ErrorType for AnyInput<'a> {
+    type Error = core::convert::Infallible;
+}
+
+impl<'a> embedded_hal::digital::InputPin for AnyInput<'a> {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.flex.is_high())
+    }
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.flex.is_high())
+    }
+}
+
+/// Output mode GPIO pad with group and number erased.
+pub struct AnyOutput<'a> {
+    flex: Flex<'a>,
+}
+
+impl<'a> AnyOutput<'a> {
+    /// Configures the pin drive strength.
+    #[inline]
+    pub fn set_drive_strength(&mut self, strength: PinDriveStrength) {
+        self.flex.set_drive_strength(strength);
+    }
+
+    /// GPIO group letter of the pad.
+    #[inline]
+    pub fn group(&self) -> char {
+        self.flex.group()
+    }
+
+    /// Pad number within its GPIO group.
+    #[inline]
+    pub fn pin(&self) -> u8 {
+        self.flex.pin()
+    }
+
+    /// Convert into a runtime configurable pad.
+    #[inline]
+    pub fn into_flex(self) -> Flex<'a> {
+        self.flex
+    }
+}
+
+impl<'a> embedded_hal::digital::ErrorType for AnyOutput<'a> {
+    type Error = core::convert::Infallible;
+}
+
+impl<'a> embedded_hal::digital::OutputPin for AnyOutput<'a> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.flex.drive(false);
+        Ok(())
+    }
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.flex.drive(true);
+        Ok(())
+    }
+}
+
+impl<'a> embedded_hal::digital::StatefulOutputPin for AnyOutput<'a> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.flex.is_set_high())
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.flex.is_set_high())
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        unsafe {
+            self.flex
+                .group_regs()
+                .output_toggle
+                .write(OutputToggle::default().toggle_output(self.flex.pin as usize));
+        }
+        Ok(())
+    }
+}
+
+impl<'a, const G: char, const N: u8> Input<'a, G, N> {
+    /// Erase group and number of the pad.
+    #[inline]
+    pub fn degrade(self) -> AnyInput<'a> {
+        AnyInput {
+            flex: self.into_flex(),
+        }
+    }
+
+    /// Convert into a runtime configurable pad.
+    #[inline]
+    pub fn into_flex(self) -> Flex<'a> {
+        Flex::from_parts(self.block(), self.group_index(), N)
+    }
+}
+
+impl<'a, const G: char, const N: u8> Output<'a, G, N> {
+    /// Erase group and number of the pad.
+    #[inline]
+    pub fn degrade(self) -> AnyOutput<'a> {
+        AnyOutput {
+            flex: self.into_flex(),
+        }
+    }
+
+    /// Convert into a runtime configurable pad.
+    #[inline]
+    pub fn into_flex(self) -> Flex<'a> {
+        Flex::from_parts(self.block(), self.group_index(), N)
+    }
+}
+
+impl<'a, const G: char, const N: u8> From<Input<'a, G, N>> for AnyInput<'a> {
+    #[inline]
+    fn from(value: Input<'a, G, N>) -> Self {
+        value.degrade()
+    }
+}
+
+impl<'a, const G: char, const N: u8> From<Output<'a, G, N>> for AnyOutput<'a> {
+    #[inline]
+    fn from(value: Output<'a, G, N>) -> Self {
+        value.degrade()
+    }
+}
@@ -44,6 +44,61 @@ impl<'a, const G: char, const N: u8> Input<'a, G, N> {
         unsafe { Self::__new(regs, Self::PIN_CONFIG.set_pin_pull(PinPull::Disabled)) }
     }
 
+    /// Enable the pin interrupt triggered by `mode`.
+    ///
+    /// Any interrupt pending from a previous configuration is cleared first.
+    #[inline]
+    pub fn enable_interrupt(&mut self, mode: GeneralIrqMode) {
+        let group = self.group();
+        unsafe {
+            group.pin_config[N as usize].modify(|v| v.set_general_irq_mode(mode));
+            group
+                .interrupt_state
+                .write(InterruptState::default().clear_interrupt(N as usize));
+            group
+                .interrupt_enable
+                .modify(|v| v.enable_interrupt(N as usize));
+        }
+    }
+
+    /// Disable the pin interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self) {
+        unsafe {
+            self.group()
+                .interrupt_enable
+                .modify(|v| v.disable_interrupt(N as usize));
+        }
+    }
+
+    /// Check if the pin interrupt is enabled.
+    #[inline]
+    pub fn is_interrupt_enabled(&self) -> bool {
+        self.group()
+            .interrupt_enable
+            .read()
+            .is_interrupt_enabled(N as usize)
+    }
+
+    /// Check if the pin interrupt is pending.
+    #[inline]
+    pub fn is_interrupt_pending(&self) -> bool {
+        self.group()
+            .interrupt_state
+            .read()
+            .is_interrupt_pending(N as usize)
+    }
+
+    /// Clear the pending pin interrupt.
+    #[inline]
+    pub fn clear_interrupt(&mut self) {
+        unsafe {
+            self.group()
+                .interrupt_state
+                .write(InterruptState::default().clear_interrupt(N as usize));
+        }
+    }
+
     /// Free current input mode GPIO pad and return the original pad.
     ///
     /// Once freed, the GPIO will be reset.
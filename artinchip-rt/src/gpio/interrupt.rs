@@ -0,0 +1,208 @@
+//! GPIO group interrupt dispatch and async wait.
+
+use super::Input;
+use super::mode::WithinGpioGroup;
+use ::core::future::poll_fn;
+use ::core::ptr::null_mut;
+use ::core::sync::atomic::{AtomicPtr, Ordering};
+use ::core::task::Poll;
+use artinchip_hal::gpio::{GeneralIrqMode, GpioGroup, InterruptState};
+use artinchip_hal::interrupt::clic::typelevel::{self, Interrupt as _};
+use embassy_sync::waitqueue::AtomicWaker;
+
+const PINS_PER_GROUP: usize = 32;
+const IRQ_GROUPS: usize = 5;
+
+const fn new_wakers() -> [AtomicWaker; PINS_PER_GROUP] {
+    [const { AtomicWaker::new() }; PINS_PER_GROUP]
+}
+
+/// Wakers of GPIO groups A to E, indexed by group and pin number.
+static GPIO_WAKERS: [[AtomicWaker; PINS_PER_GROUP]; IRQ_GROUPS] = [
+    new_wakers(),
+    new_wakers(),
+    new_wakers(),
+    new_wakers(),
+    new_wakers(),
+];
+
+const fn new_handlers() -> [AtomicPtr<()>; PINS_PER_GROUP] {
+    [const { AtomicPtr::new(null_mut()) }; PINS_PER_GROUP]
+}
+
+/// Pin handlers of GPIO groups A to E, indexed by group and pin number.
+static GPIO_HANDLERS: [[AtomicPtr<()>; PINS_PER_GROUP]; IRQ_GROUPS] = [
+    new_handlers(),
+    new_handlers(),
+    new_handlers(),
+    new_handlers(),
+    new_handlers(),
+];
+
+#[inline]
+const fn irq_group_index<const G: char>() -> usize {
+    const {
+        assert!(
+            matches!(G, 'A'..='E'),
+            "Only GPIO groups A to E have interrupt lines"
+        )
+    };
+    G as usize - 'A' as usize
+}
+
+/// GPIO group interrupt handler.
+///
+/// Bind it to the matching group interrupt, e.g. `GPIO_GRP_A =>
+/// GpioInterruptHandler<'A'>;`. Pending pins with a handler registered by
+/// [`Input::set_interrupt_handler`] are cleared and stay armed before their
+/// handler is called. Other pending pins are disabled, cleared and their
+/// tasks woken; [`Input::enable_interrupt`] arms them again.
+///
+/// The group interrupts are bound for the GPIO block of the selected SoC
+/// feature.
+pub struct GpioInterruptHandler<const G: char>;
+
+impl<const G: char> GpioInterruptHandler<G> {
+    #[inline]
+    unsafe fn handle(group: &GpioGroup) {
+        let wakers = &GPIO_WAKERS[irq_group_index::<G>()];
+        let handlers = &GPIO_HANDLERS[irq_group_index::<G>()];
+        let state = group.interrupt_state.read();
+        let enable = group.interrupt_enable.read();
+        for (pin, (waker, handler)) in wakers.iter().zip(handlers).enumerate() {
+            if !(state.is_interrupt_pending(pin) && enable.is_interrupt_enabled(pin)) {
+                continue;
+            }
+            let handler = handler.load(Ordering::Acquire);
+            unsafe {
+                if handler.is_null() {
+                    // Disable first so level triggered pins do not fire again.
+                    group.interrupt_enable.modify(|v| v.disable_interrupt(pin));
+                }
+                group
+                    .interrupt_state
+                    .write(InterruptState::default().clear_interrupt(pin));
+            }
+            if handler.is_null() {
+                waker.wake();
+            } else {
+                let handler: fn() = unsafe { ::core::mem::transmute(handler) };
+                handler();
+            }
+        }
+    }
+}
+
+/// Disables the pin interrupt if a wait is cancelled.
+struct DisableOnDrop<'r> {
+    group: &'r GpioGroup,
+    pin: usize,
+}
+
+impl Drop for DisableOnDrop<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            self.group
+                .interrupt_enable
+                .modify(|v| v.disable_interrupt(self.pin))
+        };
+    }
+}
+
+macro_rules! impl_gpio_interrupt_handler {
+    ($($group:literal => $irq:ident),+ $(,)?) => {
+        $(
+            impl typelevel::Handler<typelevel::$irq> for GpioInterruptHandler<$group> {
+                unsafe fn on_interrupt() {
+                    let regs = unsafe { &*crate::soc::GPIO::ptr() };
+                    unsafe { Self::handle(&regs.groups[irq_group_index::<$group>()]) };
+                    typelevel::$irq::clear_pending();
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "d13x")]
+impl_gpio_interrupt_handler! {
+    'A' => GPIO_GRP_A,
+    'B' => GPIO_GRP_B,
+    'C' => GPIO_GRP_C,
+    'D' => GPIO_GRP_D,
+    'E' => GPIO_GRP_E,
+}
+
+impl<'a, const G: char, const N: u8> Input<'a, G, N> {
+    /// Call `handler` from the group interrupt each time the pin interrupt
+    /// fires, instead of waking waiting tasks.
+    ///
+    /// The pin stays armed, so level triggered pins call `handler` again
+    /// until the level changes; prefer edge triggers. Tasks waiting on the pin
+    /// are not woken while a handler is set.
+    #[inline]
+    pub fn set_interrupt_handler(&mut self, handler: fn()) {
+        GPIO_HANDLERS[irq_group_index::<G>()][N as usize]
+            .store(handler as *mut (), Ordering::Release);
+    }
+
+    /// Remove the handler set by [`set_interrupt_handler`](Self::set_interrupt_handler).
+    #[inline]
+    pub fn remove_interrupt_handler(&mut self) {
+        GPIO_HANDLERS[irq_group_index::<G>()][N as usize].store(null_mut(), Ordering::Release);
+    }
+
+    /// Arm the pin interrupt with `mode` and wait until it fires.
+    ///
+    /// The pin interrupt is disabled if the wait is cancelled.
+    async fn wait_for_interrupt(&mut self, mode: GeneralIrqMode) {
+        let waker = &GPIO_WAKERS[irq_group_index::<G>()][N as usize];
+        let _guard = DisableOnDrop {
+            group: self.group(),
+            pin: N as usize,
+        };
+        self.enable_interrupt(mode);
+        poll_fn(|cx| {
+            waker.register(cx.waker());
+            // The handler disables the pin interrupt once it fires.
+            if self.is_interrupt_enabled() {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await;
+    }
+}
+
+impl<'a, const G: char, const N: u8> embedded_hal_async::digital::Wait for Input<'a, G, N> {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        if self.group().input_state.read().is_high(N as usize) {
+            return Ok(());
+        }
+        self.wait_for_interrupt(GeneralIrqMode::HighLevel).await;
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        if self.group().input_state.read().is_low(N as usize) {
+            return Ok(());
+        }
+        self.wait_for_interrupt(GeneralIrqMode::LowLevel).await;
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_interrupt(GeneralIrqMode::RisingEdge).await;
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_interrupt(GeneralIrqMode::FallingEdge).await;
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_interrupt(GeneralIrqMode::BothEdges).await;
+        Ok(())
+    }
+}
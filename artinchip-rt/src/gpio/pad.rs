@@ -1,7 +1,9 @@
 //! GPIO pad.
 
+use super::flex::Flex;
 use super::function::Function;
 use super::input::Input;
+use super::mode::WithinGpioGroup;
 use super::output::Output;
 use super::pad_ext::PadExt;
 use artinchip_hal::gpio::*;
@@ -28,6 +30,17 @@ impl<const G: char, const N: u8> GpioPad<G, N> {
     }
 }
 
+impl<const G: char, const N: u8> WithinGpioGroup<'static, G> for GpioPad<G, N> {
+    #[inline]
+    fn group(&self) -> &'static GpioGroup {
+        &self.block().groups[self.group_index()]
+    }
+    #[inline]
+    fn block(&self) -> &'static RegisterBlock {
+        unsafe { &*self.reg }
+    }
+}
+
 impl<const G: char, const N: u8> PadExt<'static, G, N> for GpioPad<G, N> {
     #[inline]
     fn into_pull_up_output(self) -> Output<'static, G, N> {
@@ -57,6 +70,10 @@ impl<const G: char, const N: u8> PadExt<'static, G, N> for GpioPad<G, N> {
     fn into_function<const F: u8>(self) -> Function<'static, G, N, F> {
         unsafe { Function::new_with_func(&*self.reg) }
     }
+    #[inline]
+    fn into_flex(self) -> Flex<'static> {
+        Flex::from_parts(self.block(), self.group_index(), N)
+    }
 }
 
 impl<'a, const G: char, const N: u8> PadExt<'a, G, N> for &'a mut GpioPad<G, N> {
@@ -88,4 +105,8 @@ impl<'a, const G: char, const N: u8> PadExt<'a, G, N> for &'a mut GpioPad<G, N>
     fn into_function<const F: u8>(self) -> Function<'a, G, N, F> {
         unsafe { Function::new_with_func(&*self.reg) }
     }
+    #[inline]
+    fn into_flex(self) -> Flex<'a> {
+        Flex::from_parts(self.block(), self.group_index(), N)
+    }
 }
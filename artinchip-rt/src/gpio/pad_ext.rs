@@ -1,5 +1,6 @@
 //! Extension traits for GPIO pads.
 
+use super::flex::Flex;
 use super::function::Function;
 use super::input::Input;
 use super::output::Output;
@@ -20,4 +21,6 @@ pub trait PadExt<'a, const G: char, const N: u8> {
     fn into_floating_input(self) -> Input<'a, G, N>;
     /// Configures the pad to operate as a function mode pad with function `F`.
     fn into_function<const F: u8>(self) -> Function<'a, G, N, F>;
+    /// Converts the pad into a runtime configurable pad, keeping its current mode.
+    fn into_flex(self) -> Flex<'a>;
 }
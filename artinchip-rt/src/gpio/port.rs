@@ -0,0 +1,147 @@
+//! Multi-pin GPIO port within one GPIO group.
+
+use super::flex::Flex;
+use artinchip_hal::gpio::*;
+
+/// Several pads of one GPIO group accessed as a single parallel value.
+///
+/// Bit `i` of a port value maps to `pads[i]`. Writes go to the group output
+/// configuration register in one access, so all selected outputs change
+/// together; reads sample the group input state in one access.
+///
+/// Writing is a masked read-modify-write of the group register inside a
+/// critical section, so only the port bits change and other pads of the
+/// group may be written concurrently, e.g. from an interrupt handler.
+pub struct GpioPort<'a, const M: usize> {
+    pads: [Flex<'a>; M],
+    mask: u32,
+    // Group bit of port bit 0 if port bits map to consecutive group bits.
+    shift: Option<u8>,
+}
+
+impl<'a, const M: usize> GpioPort<'a, M> {
+    /// Create a port from pads of one GPIO group, in port bit order.
+    ///
+    /// Pads keep their current mode; use [`set_as_output`](Self::set_as_output)
+    /// or [`set_as_input`](Self::set_as_input) to configure all of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pads` is empty, holds pads of different groups or the same
+    /// pad twice.
+    pub fn new(pads: [Flex<'a>; M]) -> Self {
+        assert!(M > 0 && M <= 32, "GpioPort holds 1 to 32 pads");
+        let group = pads[0].group();
+        let mut mask = 0u32;
+        for pad in &pads {
+            assert!(pad.group() == group, "GpioPort pads must share one group");
+            let bit = 1 << pad.pin();
+            assert!(mask & bit == 0, "GpioPort pads must be distinct");
+            mask |= bit;
+        }
+        let first = pads[0].pin();
+        let shift = pads
+            .iter()
+            .enumerate()
+            .all(|(i, pad)| pad.pin() as usize == first as usize + i)
+            .then_some(first);
+        Self { pads, mask, shift }
+    }
+
+    #[inline]
+    fn group_regs(&self) -> &'a GpioGroup {
+        self.pads[0].group_regs()
+    }
+
+    #[inline]
+    fn to_group_bits(&self, value: u32) -> u32 {
+        match self.shift {
+            Some(shift) => (value << shift) & self.mask,
+            None => self
+                .pads
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| value & (1 << i) != 0)
+                .fold(0, |bits, (_, pad)| bits | (1 << pad.pin())),
+        }
+    }
+
+    #[inline]
+    fn from_group_bits(&self, bits: u32) -> u32 {
+        match self.shift {
+            Some(shift) => (bits & self.mask) >> shift,
+            None => self
+                .pads
+                .iter()
+                .enumerate()
+                .filter(|(_, pad)| bits & (1 << pad.pin()) != 0)
+                .fold(0, |value, (i, _)| value | (1 << i)),
+        }
+    }
+
+    /// GPIO group letter of the port.
+    #[inline]
+    pub fn group(&self) -> char {
+        self.pads[0].group()
+    }
+
+    /// Mask of the group bits covered by the port.
+    #[inline]
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Configures all pads as push-pull outputs with `pull`, starting at
+    /// port value `value`.
+    pub fn set_as_output(&mut self, pull: PinPull, value: u32) {
+        self.write(value);
+        for (i, pad) in self.pads.iter_mut().enumerate() {
+            pad.set_as_output(pull, value & (1 << i) != 0);
+        }
+    }
+
+    /// Configures all pads as inputs with `pull`.
+    pub fn set_as_input(&mut self, pull: PinPull) {
+        for pad in self.pads.iter_mut() {
+            pad.set_as_input(pull);
+        }
+    }
+
+    /// Configures the drive strength of all pads.
+    pub fn set_drive_strength(&mut self, strength: PinDriveStrength) {
+        for pad in self.pads.iter_mut() {
+            pad.set_drive_strength(strength);
+        }
+    }
+
+    /// Write port value `value`; bits above the port width are ignored.
+    ///
+    /// Other pads of the group written from interrupts are not affected.
+    #[inline]
+    pub fn write(&mut self, value: u32) {
+        let bits = self.to_group_bits(value);
+        critical_section::with(|_| unsafe {
+            self.group_regs()
+                .output_config
+                .modify(|v| v.set_masked(self.mask, bits));
+        });
+    }
+
+    /// Read the port value last written.
+    #[inline]
+    pub fn output(&self) -> u32 {
+        self.from_group_bits(self.group_regs().output_config.read().masked(self.mask))
+    }
+
+    /// Sample the port pad levels.
+    #[inline]
+    pub fn read(&self) -> u32 {
+        self.from_group_bits(self.group_regs().input_state.read().masked(self.mask))
+    }
+
+    /// Release the pads.
+    #[inline]
+    pub fn free(self) -> [Flex<'a>; M] {
+        self.pads
+    }
+}
@@ -7,10 +7,17 @@ pub use artinchip_rt_macros::pbp_entry;
 #[macro_use]
 pub mod macros;
 pub mod core;
+#[cfg(feature = "embassy")]
+pub mod embassy;
 pub mod gpio;
+#[cfg(feature = "panic-handler")]
+pub mod panic;
 pub mod pbp;
 pub mod soc;
 
+#[cfg(all(feature = "panic-handler", not(feature = "d13x")))]
+compile_error!("The `panic-handler` feature is only supported with the `d13x` feature");
+
 /// ArtInChip RT prelude.
 pub mod prelude {
     pub use crate::gpio::PadExt as _;
@@ -68,6 +68,53 @@ macro_rules! uart_rx {
     };
 }
 
+/// Implements the `RequestToSend`, `DriverEnable` and `UartPad` traits for multiple UART pins.
+///
+/// The RTS output doubles as the RS-485 driver enable (DE) output.
+#[allow(unused_macros)]
+macro_rules! uart_rts {
+    ($uart_num:expr, $(($port:literal, $pin:expr, $func:expr)),+) => {
+        $(
+            impl artinchip_hal::uart::UartPad<$uart_num> for crate::gpio::Function<'_, $port, $pin, $func> {}
+            impl artinchip_hal::uart::RequestToSend<$uart_num> for crate::gpio::Function<'_, $port, $pin, $func> {}
+            impl artinchip_hal::uart::DriverEnable<$uart_num> for crate::gpio::Function<'_, $port, $pin, $func> {}
+
+            paste! {
+                impl<'a> crate::gpio::GpioPad<$port, $pin> {
+                    #[inline]
+                    pub fn [<into_uart $uart_num _rts>](self) -> crate::gpio::Function<'a, $port, $pin, $func> {
+                        self.into_function::<$func>()
+                    }
+                    #[inline]
+                    pub fn [<into_uart $uart_num _de>](self) -> crate::gpio::Function<'a, $port, $pin, $func> {
+                        self.into_function::<$func>()
+                    }
+                }
+            }
+        )+
+    };
+}
+
+/// Implements the `ClearToSend` and `UartPad` traits for multiple UART pins.
+#[allow(unused_macros)]
+macro_rules! uart_cts {
+    ($uart_num:expr, $(($port:literal, $pin:expr, $func:expr)),+) => {
+        $(
+            impl artinchip_hal::uart::UartPad<$uart_num> for crate::gpio::Function<'_, $port, $pin, $func> {}
+            impl artinchip_hal::uart::ClearToSend<$uart_num> for crate::gpio::Function<'_, $port, $pin, $func> {}
+
+            paste! {
+                impl<'a> crate::gpio::GpioPad<$port, $pin> {
+                    #[inline]
+                    pub fn [<into_uart $uart_num _cts>](self) -> crate::gpio::Function<'a, $port, $pin, $func> {
+                        self.into_function::<$func>()
+                    }
+                }
+            }
+        )+
+    };
+}
+
 // QSPI pin multiplexer macros.
 
 /// Implements the `SerialClock` traits for multiple QSPI pins.
@@ -0,0 +1,180 @@
+//! Panic handler with UART report and watchdog reset.
+//!
+//! On panic, the message is written to the writer registered with
+//! [`set_panic_writer`], or to UART0 with raw polled 16550 accesses if none is
+//! registered. A compact [`PanicCode`] is then stored in the RTC backup domain
+//! and the watchdog resets the chip. After reboot, check the reason reported by
+//! `wri::ResetInfo` and fetch the code with [`PanicCode::take`].
+
+use crate::soc::d13x::{CMU, RTC, UART0, WDOG};
+use ::core::cell::RefCell;
+use ::core::fmt::{self, Write};
+use ::core::panic::PanicInfo;
+use artinchip_hal::cmu::Cmu;
+use artinchip_hal::rtc::RealTime;
+use artinchip_hal::uart::RegisterBlock as UartRegisterBlock;
+use artinchip_hal::wdog::WdogDriver;
+use artinchip_hal::wri::ResetReason;
+use critical_section::Mutex;
+
+/// Reboot reason recorded in RTC boot info on panic.
+///
+/// Matches `REBOOT_REASON_PANIC` of luban-lite.
+pub const REBOOT_REASON_PANIC: u8 = 10;
+
+/// First RTC system backup register used to store the panic code.
+///
+/// Five registers starting here are used: a magic byte, the line number and
+/// a hash of the file name.
+pub const PANIC_BACKUP_INDEX: usize = 10;
+
+const PANIC_MAGIC: u8 = 0xA5;
+const TX_SPIN_LIMIT: usize = 100_000;
+
+static PANIC_WRITER: Mutex<RefCell<Option<&'static mut (dyn Write + Send)>>> =
+    Mutex::new(RefCell::new(None));
+
+/// Register the writer used to report panics, e.g. an initialized UART.
+pub fn set_panic_writer(writer: &'static mut (dyn Write + Send)) {
+    critical_section::with(|cs| {
+        PANIC_WRITER.borrow(cs).replace(Some(writer));
+    });
+}
+
+/// Compact panic location kept across the watchdog reset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PanicCode {
+    /// Source line of the panic, saturated to `u16::MAX`.
+    pub line: u16,
+    /// FNV-1a hash of the source file name, folded to 16 bits.
+    pub file_hash: u16,
+}
+
+impl PanicCode {
+    /// Build the code of a panic location.
+    pub fn new(file: &str, line: u32) -> Self {
+        let mut hash: u32 = 0x811C9DC5;
+        for &b in file.as_bytes() {
+            hash ^= b as u32;
+            hash = hash.wrapping_mul(0x01000193);
+        }
+        Self {
+            line: line.min(u16::MAX as u32) as u16,
+            file_hash: ((hash >> 16) ^ hash) as u16,
+        }
+    }
+
+    /// Store the code in the RTC backup registers.
+    pub fn store(&self, rtc: &mut RealTime<'_>) {
+        let [line_lo, line_hi] = self.line.to_le_bytes();
+        let [hash_lo, hash_hi] = self.file_hash.to_le_bytes();
+        rtc.set_backup(PANIC_BACKUP_INDEX + 1, line_lo);
+        rtc.set_backup(PANIC_BACKUP_INDEX + 2, line_hi);
+        rtc.set_backup(PANIC_BACKUP_INDEX + 3, hash_lo);
+        rtc.set_backup(PANIC_BACKUP_INDEX + 4, hash_hi);
+        rtc.set_backup(PANIC_BACKUP_INDEX, PANIC_MAGIC);
+        rtc.set_reboot_reason(REBOOT_REASON_PANIC);
+    }
+
+    /// Fetch and clear the code stored before the last reset.
+    ///
+    /// `reason` is the value returned by `ResetInfo::reason`; a code is only
+    /// returned if the last reset was caused by the watchdog.
+    pub fn take(reason: ResetReason, rtc: &mut RealTime<'_>) -> Option<Self> {
+        if rtc.backup(PANIC_BACKUP_INDEX) != PANIC_MAGIC {
+            return None;
+        }
+        rtc.set_backup(PANIC_BACKUP_INDEX, 0);
+        if reason != ResetReason::WatchdogReset || rtc.reboot_reason() != REBOOT_REASON_PANIC {
+            return None;
+        }
+        rtc.set_reboot_reason(0);
+        Some(Self {
+            line: u16::from_le_bytes([
+                rtc.backup(PANIC_BACKUP_INDEX + 1),
+                rtc.backup(PANIC_BACKUP_INDEX + 2),
+            ]),
+            file_hash: u16::from_le_bytes([
+                rtc.backup(PANIC_BACKUP_INDEX + 3),
+                rtc.backup(PANIC_BACKUP_INDEX + 4),
+            ]),
+        })
+    }
+}
+
+/// Raw polled 16550 writer for UART0, used when no writer is registered.
+///
+/// Relies on the UART having been set up earlier, by the boot ROM or by the
+/// application; gives up on a byte if the transmitter never becomes ready.
+struct RawUart(&'static UartRegisterBlock);
+
+impl Write for RawUart {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let uart16550 = &self.0.uart16550;
+        for &b in s.as_bytes() {
+            let mut spin = 0;
+            while !uart16550.lsr().read().is_transmitter_fifo_empty() {
+                spin += 1;
+                if spin >= TX_SPIN_LIMIT {
+                    return Err(fmt::Error);
+                }
+                ::core::hint::spin_loop();
+            }
+            uart16550.rbr_thr().tx_data(b);
+        }
+        Ok(())
+    }
+}
+
+fn report(info: &PanicInfo, w: &mut dyn Write) {
+    match info.location() {
+        Some(l) => writeln!(
+            w,
+            "\r\npanicked at {}:{}:{}:",
+            l.file(),
+            l.line(),
+            l.column()
+        )
+        .ok(),
+        None => writeln!(w, "\r\npanicked:").ok(),
+    };
+    writeln!(w, "{}\r", info.message()).ok();
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    unsafe {
+        ::core::arch::asm!("csrci mstatus, 0x8");
+    }
+
+    critical_section::with(|cs| {
+        // Already borrowed if the registered writer itself panicked.
+        let mut writer = PANIC_WRITER.borrow(cs).try_borrow_mut().ok();
+        match writer.as_mut().and_then(|w| w.as_deref_mut()) {
+            Some(w) => report(info, w),
+            None => report(info, &mut RawUart(unsafe { &*UART0::ptr() })),
+        }
+    });
+
+    let code = match info.location() {
+        Some(l) => PanicCode::new(l.file(), l.line()),
+        None => PanicCode::new("", 0),
+    };
+    // Panicking code may own the peripherals, so reclaim them here. The RTC
+    // keeps its configuration; only its bus clock is turned on.
+    let mut cmu = Cmu::__new(CMU::ptr());
+    unsafe {
+        cmu.register_block()
+            .clock_rtc
+            .modify(|v| v.enable_bus_clk())
+    };
+    let mut rtc = RealTime::attach(unsafe { &*RTC::ptr() });
+    code.store(&mut rtc);
+
+    let mut wdog = WdogDriver::new(unsafe { &*WDOG::ptr() }, &mut cmu);
+    wdog.configure_scene_and_apply(0, 0, 0, 0);
+
+    loop {
+        ::core::hint::spin_loop();
+    }
+}
@@ -1,3 +1,7 @@
 //! ArtInChip SoC periphral support module.
 
 pub mod d13x;
+
+/// GPIO register block of the selected SoC.
+#[cfg(feature = "d13x")]
+pub(crate) use d13x::GPIO;
@@ -263,6 +263,12 @@ uart_tx!(6, ('B', 1, 5), ('E', 6, 5), ('P', 14, 5)); // UART6
 uart_rx!(6, ('B', 2, 5), ('E', 7, 5), ('P', 15, 5));
 uart_tx!(7, ('B', 10, 5), ('E', 8, 5), ('P', 16, 5)); // UART7
 uart_rx!(7, ('B', 11, 5), ('E', 9, 5), ('P', 17, 5));
+uart_rts!(0, ('A', 2, 6)); // Flow control and RS-485 DE
+uart_cts!(0, ('A', 3, 6));
+uart_rts!(1, ('A', 4, 6));
+uart_cts!(1, ('A', 5, 6));
+uart_rts!(2, ('A', 6, 6));
+uart_cts!(2, ('A', 7, 6));
 
 // I2C pin mux for D13x series.
 i2c_scl!(0, ('A', 0, 4), ('D', 0, 4), ('E', 14, 4)); // I2C0
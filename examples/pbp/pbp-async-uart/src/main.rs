@@ -11,6 +11,9 @@ use embedded_io_async::Write as _;
 use heapless::String;
 use panic_halt as _;
 
+static mut TX_BUF: [u8; 256] = [0; 256];
+static mut RX_BUF: [u8; 64] = [0; 64];
+
 clic_bind_interrupts!(struct Irqs {
     UART0 => AsyncUartHandler<0>;
 });
@@ -48,9 +51,18 @@ fn pbp_main(_boot_param: u32, _private_data: &[u8]) {
 
     let uart0_tx = p.gpioa.pa0.into_uart0_tx();
     let uart0_rx = p.gpioa.pa1.into_uart0_rx();
-    let mut uart0_async =
-        p.uart0
-            .new_async(uart0_tx, uart0_rx, UartConfig::default(), &mut p.cmu, Irqs);
+    let buffers = AsyncBuffers {
+        tx: unsafe { &mut *core::ptr::addr_of_mut!(TX_BUF) },
+        rx: unsafe { &mut *core::ptr::addr_of_mut!(RX_BUF) },
+    };
+    let mut uart0_async = p.uart0.new_async(
+        uart0_tx,
+        uart0_rx,
+        buffers,
+        UartConfig::default(),
+        &mut p.cmu,
+        Irqs,
+    );
 
     block_on(async {
         let mut buf: String<128> = String::new();
@@ -73,8 +73,8 @@ fn pbp_main(_boot_param: u32, _private_data: &[u8]) {
                     )
                     .ok();
                 }
-                Err(_) => {
-                    writeln!(uart0, "Failed to read ID! ").ok();
+                Err(e) => {
+                    writeln!(uart0, "Failed to read ID: {}", e).ok();
                 }
             }
 